@@ -0,0 +1,263 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process;
+
+use colored::Color;
+use json::JsonValue;
+use json_highlight_writer::selector::JsonPath;
+use json_highlight_writer::{pointer, highlight_parse_error, HighlightGenerator, PrettyConfig, SliceStyles, Style};
+
+const USAGE: &str = "\
+Usage: jhl [OPTIONS] [FILE]...
+
+Prints JSON documents, highlighting the nodes matched by selectors.
+Reads from stdin when no FILE, or `-`, is given.
+
+Options:
+  -s, --select [COLOR=]SELECTOR  Highlights the nodes matched by a JSON Pointer (`/payload/id`)
+                                 or a JSONPath expression (`$..id`), in COLOR if given
+  -p, --pretty                   Prints across multiple lines, indented by two spaces
+  -r, --raw                      Prints the documents exactly as they were written
+      --color WHEN               Colors the output: always, never or auto (the default),
+                                 which colors it when writing to a terminal
+  -h, --help                     Prints this message";
+
+/// Colors given to selectors which don't name their own, in order
+const PALETTE: [Color; 6] = [Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorChoice {
+  Always,
+  Never,
+  Auto
+}
+
+impl ColorChoice {
+  /// Whether to color a stream, given whether it is written to a terminal
+  fn colors(self, is_terminal: bool) -> bool {
+    match self {
+      ColorChoice::Always => true,
+      ColorChoice::Never => false,
+      ColorChoice::Auto => is_terminal && env::var_os("NO_COLOR").is_none()
+    }
+  }
+}
+
+#[derive(Debug)]
+enum Query {
+  Pointer(String),
+  Path(JsonPath)
+}
+
+#[derive(Debug)]
+struct Selector {
+  query: Query,
+  style: Style
+}
+
+impl Selector {
+  fn select<'a>(&self, json: &'a JsonValue) -> Vec<&'a JsonValue> {
+    match self.query {
+      Query::Pointer(ref pointer) => pointer::resolve(json, pointer).into_iter().collect(),
+      Query::Path(ref path) => path.select(json)
+    }
+  }
+}
+
+#[derive(Debug)]
+struct Options {
+  selectors: Vec<Selector>,
+  files: Vec<String>,
+  pretty: bool,
+  raw: bool,
+  color: ColorChoice,
+  help: bool
+}
+
+#[derive(Debug, PartialEq)]
+struct UsageError(String);
+
+impl fmt::Display for UsageError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+fn main() {
+  let options = match parse_args(env::args().skip(1)) {
+    Ok(options) => options,
+    Err(error) => {
+      eprintln!("jhl: {}\n\n{}", error, USAGE);
+      process::exit(2);
+    }
+  };
+  if options.help {
+    println!("{}", USAGE);
+    return;
+  }
+
+  colored::control::set_override(options.color.colors(io::stdout().is_terminal()));
+
+  let files = if options.files.is_empty() { vec!["-".to_string()] } else { options.files.clone() };
+  let mut failed = false;
+  for file in &files {
+    if let Err(message) = print_file(file, &options) {
+      eprintln!("jhl: {}", message);
+      failed = true;
+    }
+  }
+  if failed {
+    process::exit(1);
+  }
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, UsageError> {
+  let mut options = Options {
+    selectors: vec![],
+    files: vec![],
+    pretty: false,
+    raw: false,
+    color: ColorChoice::Auto,
+    help: false
+  };
+  let mut args = args.into_iter();
+  let mut only_files = false;
+  while let Some(arg) = args.next() {
+    if only_files || arg == "-" || !arg.starts_with('-') {
+      options.files.push(arg);
+      continue;
+    }
+    let (flag, inline_value) = match arg.find('=') {
+      Some(index) if arg.starts_with("--") => (&arg[..index], Some(arg[index + 1..].to_string())),
+      _ => (arg.as_str(), None)
+    };
+    let mut value = || inline_value.clone()
+      .or_else(|| args.next())
+      .ok_or_else(|| UsageError(format!("{} expects a value", flag)));
+    match flag {
+      "--" => only_files = true,
+      "-s" | "--select" => {
+        let index = options.selectors.len();
+        options.selectors.push(parse_selector(&value()?, index)?);
+      },
+      "-p" | "--pretty" => options.pretty = true,
+      "-r" | "--raw" => options.raw = true,
+      "--color" => options.color = match value()?.as_str() {
+        "always" => ColorChoice::Always,
+        "never" => ColorChoice::Never,
+        "auto" => ColorChoice::Auto,
+        other => return Err(UsageError(format!("--color expects always, never or auto, not {:?}", other)))
+      },
+      "-h" | "--help" => options.help = true,
+      _ => return Err(UsageError(format!("Unknown option {}", arg)))
+    }
+  }
+  Ok(options)
+}
+
+/// Parses `[COLOR=]SELECTOR`, where a selector without a color of its own gets the
+/// `index`th color of the palette
+fn parse_selector(arg: &str, index: usize) -> Result<Selector, UsageError> {
+  let (color, expression) = match arg.find('=') {
+    Some(split) if !arg.starts_with('/') && !arg.starts_with('$') => {
+      let name = &arg[..split];
+      let color = name.replace('-', " ").parse::<Color>()
+        .map_err(|_| UsageError(format!("Unknown color {:?}", name)))?;
+      (color, &arg[split + 1..])
+    },
+    _ => (PALETTE[index % PALETTE.len()], arg)
+  };
+  let query = if expression.starts_with('/') {
+    pointer::parse(expression).map_err(|error| UsageError(error.to_string()))?;
+    Query::Pointer(expression.to_string())
+  } else {
+    Query::Path(JsonPath::parse(expression).map_err(|error| UsageError(error.to_string()))?)
+  };
+  Ok(Selector { query, style: color.into() })
+}
+
+fn print_file(file: &str, options: &Options) -> Result<(), String> {
+  let name = if file == "-" { "stdin" } else { file };
+  let mut input = String::new();
+  if file == "-" {
+    io::stdin().read_to_string(&mut input)
+  } else {
+    fs::File::open(file).and_then(|mut source| source.read_to_string(&mut input))
+  }.map_err(|error| format!("Can't read {}: {}", name, error))?;
+  let json = json::parse(&input).map_err(|error| {
+    // Parse errors go to stderr, which may be a terminal when stdout isn't, or the other way round
+    colored::control::set_override(options.color.colors(io::stderr().is_terminal()));
+    let rendered = highlight_parse_error(&input, &error);
+    colored::control::set_override(options.color.colors(io::stdout().is_terminal()));
+    format!("Can't parse {}\n{}", name, rendered)
+  })?;
+
+  let mut slices = vec![];
+  let mut styles = vec![];
+  for selector in &options.selectors {
+    for node in selector.select(&json) {
+      slices.push(node);
+      styles.push(selector.style);
+    }
+  }
+
+  let stdout = io::stdout();
+  let mut out = stdout.lock();
+  let mut gen = HighlightGenerator::new()
+    .with_highlight_color(SliceStyles::new(styles))
+    .with_output(&mut out);
+  if options.pretty {
+    gen = gen.with_pretty(PrettyConfig::default());
+  }
+  if options.raw {
+    gen.write_source_with_highlight(&input, &json, &mut slices)
+  } else {
+    gen.write_json_with_highlight(&json, &mut slices).map_err(Into::into)
+  }.map_err(|error| format!("Can't write {}: {}", name, error))?;
+  drop(gen);
+  if options.raw && input.ends_with('\n') {
+    return Ok(());
+  }
+  writeln!(out).map_err(|error| format!("Can't write {}: {}", name, error))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  #[test]
+  fn should_parse_selectors_with_and_without_colors() {
+    let options = parse_args(args(&["-s", "/code", "--select=bright-blue=$..id", "--select", "green=/a=b", "data.json"])).unwrap();
+
+    assert_eq!(options.files, vec!["data.json"]);
+    assert_eq!(options.color, ColorChoice::Auto);
+    assert!(matches!(options.selectors[0].query, Query::Pointer(ref pointer) if pointer == "/code"));
+    assert_eq!(options.selectors[0].style, Color::Red.into());
+    assert!(matches!(options.selectors[1].query, Query::Path(_)));
+    assert_eq!(options.selectors[1].style, Color::BrightBlue.into());
+    assert!(matches!(options.selectors[2].query, Query::Pointer(ref pointer) if pointer == "/a=b"));
+    assert_eq!(options.selectors[2].style, Color::Green.into());
+  }
+
+  #[test]
+  fn should_reject_invalid_arguments() {
+    assert_eq!(parse_args(args(&["--color", "sometimes"])).unwrap_err(), UsageError("--color expects always, never or auto, not \"sometimes\"".to_string()));
+    assert_eq!(parse_args(args(&["-s", "mauve=/code"])).unwrap_err(), UsageError("Unknown color \"mauve\"".to_string()));
+    assert_eq!(parse_args(args(&["--select"])).unwrap_err(), UsageError("--select expects a value".to_string()));
+    assert!(parse_args(args(&["-s", "$.items[?(@.id =="])).is_err());
+    assert!(parse_args(args(&["-s", "red=code"])).is_err());
+  }
+
+  #[test]
+  fn should_decide_color_per_stream() {
+    assert!(ColorChoice::Always.colors(false));
+    assert!(!ColorChoice::Never.colors(true));
+    assert!(!ColorChoice::Auto.colors(false));
+  }
+}
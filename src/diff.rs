@@ -0,0 +1,215 @@
+use std::io;
+use json::JsonValue;
+use colored::*;
+
+use crate::highlight::HighlightGenerator;
+use crate::generator::codegen::Generator;
+use crate::theme::Token;
+use crate::style::Style;
+
+/// Structural difference between an expected and an actual JSON object.
+/// Arrays are compared item by item, by index.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diff<'a> {
+  Same(&'a JsonValue),
+  Added(&'a JsonValue),
+  Removed(&'a JsonValue),
+  Changed(&'a JsonValue, &'a JsonValue),
+  Object(Vec<(&'a str, Diff<'a>)>),
+  Array(Vec<Diff<'a>>)
+}
+
+impl<'a> Diff<'a> {
+  pub fn is_same(&self) -> bool {
+    matches!(self, Diff::Same(_))
+  }
+}
+
+pub fn diff<'a>(expected: &'a JsonValue, actual: &'a JsonValue) -> Diff<'a> {
+  match (expected, actual) {
+    (JsonValue::Object(ref expected_object), JsonValue::Object(ref actual_object)) => {
+      let mut entries : Vec<(&str, Diff)> = expected_object.iter()
+        .map(|(key, value)| match actual_object.get(key) {
+          Some(actual_value) => (key, diff(value, actual_value)),
+          None => (key, Diff::Removed(value))
+        })
+        .collect();
+      entries.extend(
+        actual_object.iter()
+          .filter(|(key, _)| expected_object.get(key).is_none())
+          .map(|(key, value)| (key, Diff::Added(value)))
+      );
+      if entries.iter().all(|(_, entry)| entry.is_same()) {
+        Diff::Same(actual)
+      } else {
+        Diff::Object(entries)
+      }
+    },
+    (JsonValue::Array(ref expected_array), JsonValue::Array(ref actual_array)) => {
+      let items : Vec<Diff> = (0..expected_array.len().max(actual_array.len()))
+        .map(|index| match (expected_array.get(index), actual_array.get(index)) {
+          (Some(expected_item), Some(actual_item)) => diff(expected_item, actual_item),
+          (Some(expected_item), None) => Diff::Removed(expected_item),
+          (None, Some(actual_item)) => Diff::Added(actual_item),
+          (None, None) => unreachable!()
+        })
+        .collect();
+      if items.iter().all(Diff::is_same) {
+        Diff::Same(actual)
+      } else {
+        Diff::Array(items)
+      }
+    },
+    _ if expected == actual => Diff::Same(actual),
+    _ => Diff::Changed(expected, actual)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffColors {
+  pub added: Style,
+  pub removed: Style,
+  pub changed: Style
+}
+
+impl Default for DiffColors {
+  fn default() -> Self {
+    DiffColors {
+      added: Color::Green.into(),
+      removed: Color::Red.into(),
+      changed: Color::Yellow.into()
+    }
+  }
+}
+
+impl<'a> HighlightGenerator<'a> {
+  /// Writes the actual JSON object, highlighting additions, removals and changed values
+  /// (written as `old → new`) in their own styles
+  pub fn write_diff(&mut self, diff: &Diff, colors: DiffColors) -> io::Result<()> {
+    write_node(self, diff, colors)?;
+    self.finish()
+  }
+}
+
+fn write_node(gen: &mut HighlightGenerator, diff: &Diff, colors: DiffColors) -> io::Result<()> {
+  match diff {
+    Diff::Same(value) => gen.write_json(value),
+    Diff::Added(value) => gen.write_highlighted(colors.added, |gen| gen.write_json(value)),
+    Diff::Removed(value) => gen.write_highlighted(colors.removed, |gen| gen.write_json(value)),
+    Diff::Changed(old, new) => gen.write_highlighted(colors.changed, |gen| {
+      gen.write_json(old)?;
+      gen.write(" → ".as_bytes())?;
+      gen.write_json(new)
+    }),
+    Diff::Object(entries) => {
+      gen.write_punctuation(b'{')?;
+      gen.indent();
+      for (index, (key, entry)) in entries.iter().enumerate() {
+        if index > 0 {
+          gen.write_punctuation(b',')?;
+        }
+        gen.new_line()?;
+        match entry {
+          Diff::Added(value) => gen.write_highlighted(colors.added, |gen| write_entry(gen, key, value))?,
+          Diff::Removed(value) => gen.write_highlighted(colors.removed, |gen| write_entry(gen, key, value))?,
+          _ => {
+            gen.write_string_token(Token::Key, key)?;
+            gen.write_colon()?;
+            write_node(gen, entry, colors)?;
+          }
+        }
+      }
+      gen.dedent();
+      gen.new_line()?;
+      gen.write_punctuation(b'}')
+    },
+    Diff::Array(items) => {
+      gen.write_punctuation(b'[')?;
+      gen.indent();
+      for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+          gen.write_punctuation(b',')?;
+        }
+        gen.new_line()?;
+        write_node(gen, item, colors)?;
+      }
+      gen.dedent();
+      gen.new_line()?;
+      gen.write_punctuation(b']')
+    }
+  }
+}
+
+fn write_entry(gen: &mut HighlightGenerator, key: &str, value: &JsonValue) -> io::Result<()> {
+  gen.write_string_token(Token::Key, key)?;
+  gen.write_colon()?;
+  gen.write_json(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use json::*;
+
+  #[test]
+  fn should_find_added_removed_and_changed_nodes() {
+    let expected = object!{
+      "code" => 200,
+      "payload" => object!{ "id" => 1, "tags" => array!["a", "b"] },
+      "legacy" => true
+    };
+    let actual = object!{
+      "code" => 500,
+      "payload" => object!{ "id" => 1, "tags" => array!["a"] },
+      "error" => "boom"
+    };
+
+    assert_eq!(
+      diff(&expected, &actual),
+      Diff::Object(vec![
+        ("code", Diff::Changed(&expected["code"], &actual["code"])),
+        ("payload", Diff::Object(vec![
+          ("id", Diff::Same(&actual["payload"]["id"])),
+          ("tags", Diff::Array(vec![
+            Diff::Same(&actual["payload"]["tags"][0]),
+            Diff::Removed(&expected["payload"]["tags"][1])
+          ]))
+        ])),
+        ("legacy", Diff::Removed(&expected["legacy"])),
+        ("error", Diff::Added(&actual["error"]))
+      ])
+    );
+    assert!(diff(&expected, &expected.clone()).is_same());
+  }
+
+  #[test]
+  fn should_write_diff_with_highlights() {
+    let expected = object!{
+      "code" => 200,
+      "tags" => array!["a", "b"],
+      "legacy" => true
+    };
+    let actual = object!{
+      "code" => 500,
+      "tags" => array!["a", "b"],
+      "error" => "boom"
+    };
+
+    let mut gen = HighlightGenerator::new();
+    gen.write_diff(&diff(&expected, &actual), DiffColors::default()).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}{}{}{}{}",
+        r#"{"code":"#,
+        "200 → 500".yellow(),
+        r#","tags":["a","b"],"#,
+        r#""legacy":true"#.red(),
+        ",",
+        r#""error":"boom""#.green(),
+        "}"
+      )
+    );
+  }
+}
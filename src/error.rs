@@ -0,0 +1,63 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use crate::selector::ParseError;
+
+#[derive(Debug)]
+pub enum Error {
+  /// Writing the highlighted JSON failed
+  Io(io::Error),
+  /// A JSON Pointer was malformed
+  InvalidPointer(String),
+  /// A JSONPath expression couldn't be parsed
+  InvalidSelector(ParseError),
+  /// The source text of a JSON object couldn't be parsed
+  InvalidSource(json::Error),
+  /// Some of the requested slices weren't found in the JSON object,
+  /// identified by their index in the request
+  UnmatchedSlices(Vec<usize>)
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Io(error) => write!(f, "Failed to write JSON: {}", error),
+      Error::InvalidPointer(pointer) => write!(f, "Invalid JSON Pointer: {:?}", pointer),
+      Error::InvalidSelector(error) => write!(f, "{}", error),
+      Error::InvalidSource(error) => write!(f, "Invalid JSON source: {}", error),
+      Error::UnmatchedSlices(indices) => write!(f, "Slices at indices {:?} weren't found in the JSON object", indices)
+    }
+  }
+}
+
+impl error::Error for Error {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      Error::Io(error) => Some(error),
+      Error::InvalidSelector(error) => Some(error),
+      Error::InvalidSource(error) => Some(error),
+      _ => None
+    }
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(error: io::Error) -> Self {
+    Error::Io(error)
+  }
+}
+
+impl From<ParseError> for Error {
+  fn from(error: ParseError) -> Self {
+    Error::InvalidSelector(error)
+  }
+}
+
+impl From<json::Error> for Error {
+  fn from(error: json::Error) -> Self {
+    Error::InvalidSource(error)
+  }
+}
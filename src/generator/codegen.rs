@@ -1,11 +1,8 @@
-use std::ptr;
 use std::io::Write;
-use json::JsonValue;
-use json::number::Number;
-use json::object::Object;
 use std::io;
 
-use super::print_dec;
+use crate::theme::Token;
+use crate::tree::JsonTree;
 
 const QU: u8 = b'"';
 const BS: u8 = b'\\';
@@ -40,6 +37,7 @@ static ESCAPED: [u8; 256] = [
 
 pub trait Generator {
     type T: Write;
+    type Tree: JsonTree;
 
     fn get_writer(&mut self) -> &mut Self::T;
 
@@ -55,14 +53,70 @@ pub trait Generator {
 
     fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()>;
 
+    #[inline(always)]
+    fn new_line(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn indent(&mut self) {}
+
+    #[inline(always)]
+    fn dedent(&mut self) {}
+
+    #[inline(always)]
+    fn begin_token(&mut self, _token: Token) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn end_token(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn write_token(&mut self, token: Token, slice: &[u8]) -> io::Result<()> {
+        self.begin_token(token)?;
+        self.write(slice)?;
+        self.end_token()
+    }
+
+    #[inline(always)]
+    fn write_punctuation(&mut self, ch: u8) -> io::Result<()> {
+        self.begin_token(Token::Punctuation)?;
+        self.write_char(ch)?;
+        self.end_token()
+    }
+
+    #[inline(always)]
+    fn write_string_token(&mut self, token: Token, string: &str) -> io::Result<()> {
+        self.begin_token(token)?;
+        self.write_string(string)?;
+        self.end_token()
+    }
+
+    #[inline(always)]
+    fn write_number_token(&mut self, json: &Self::Tree) -> io::Result<()> {
+        self.begin_token(Token::Number)?;
+        json.write_number(self.get_writer())?;
+        self.end_token()
+    }
+
+    #[inline(always)]
+    fn write_colon(&mut self) -> io::Result<()> {
+        self.begin_token(Token::Punctuation)?;
+        self.write_min(b": ", b':')?;
+        self.end_token()
+    }
+
     #[inline(never)]
     fn write_string_complex(&mut self, string: &str, mut start: usize) -> io::Result<()> {
-        self.write(string[ .. start].as_bytes())?;
+        self.write(&string.as_bytes()[ .. start])?;
 
         for (index, ch) in string.bytes().enumerate().skip(start) {
             let escape = ESCAPED[ch as usize];
             if escape > 0 {
-                self.write(string[start .. index].as_bytes())?;
+                self.write(&string.as_bytes()[start .. index])?;
                 self.write(&[b'\\', escape])?;
                 start = index + 1;
             }
@@ -70,7 +124,7 @@ pub trait Generator {
                 write!(self.get_writer(), "{:04x}", ch)?;
             }
         }
-        self.write(string[start ..].as_bytes())?;
+        self.write(&string.as_bytes()[start ..])?;
 
         self.write_char(b'"')
     }
@@ -89,97 +143,9 @@ pub trait Generator {
         self.write_char(b'"')
     }
 
-    #[inline(always)]
-    fn write_number(&mut self, num: &Number) -> io::Result<()> {
-        if num.is_nan() {
-            return self.write(b"null");
-        }
-        let (positive, mantissa, exponent) = num.as_parts();
-        unsafe {
-            print_dec::write(
-                self.get_writer(),
-                positive,
-                mantissa,
-                exponent
-            )
-        }
-    }
-
-    #[inline(always)]
-    fn write_object(&mut self, object: &Object) -> io::Result<()> {
-        self.write_char(b'{')?;
-        let mut iter = object.iter();
-
-        if let Some((key, value)) = iter.next() {
-            self.write_string(key)?;
-            self.write_min(b": ", b':')?;
-            self.write_json(value)?;
-        } else {
-            self.write_char(b'}')?;
-            return Ok(());
-        }
-
-        for (key, value) in iter {
-            self.write_char(b',')?;
-            self.write_string(key)?;
-            self.write_min(b": ", b':')?;
-            self.write_json(value)?;
-        }
+    fn write_object(&mut self, json: &Self::Tree) -> io::Result<()>;
 
-        self.write_char(b'}')
-    }
+    fn write_array(&mut self, json: &Self::Tree) -> io::Result<()>;
 
-    fn write_json(&mut self, json: &JsonValue) -> io::Result<()> {
-        match *json {
-            JsonValue::Null               => self.write(b"null"),
-            JsonValue::Short(ref short)   => self.write_string(short.as_str()),
-            JsonValue::String(ref string) => self.write_string(string),
-            JsonValue::Number(ref number) => self.write_number(number),
-            JsonValue::Boolean(true)      => self.write(b"true"),
-            JsonValue::Boolean(false)     => self.write(b"false"),
-            JsonValue::Array(ref array)   => {
-                self.write_char(b'[')?;
-                let mut iter = array.iter();
-
-                if let Some(item) = iter.next() {
-                    self.write_json(item)?;
-                } else {
-                    self.write_char(b']')?;
-                    return Ok(());
-                }
-
-                for item in iter {
-                    self.write_char(b',')?;
-                    self.write_json(item)?;
-                }
-
-                self.write_char(b']')
-            },
-            JsonValue::Object(ref object) => {
-                self.write_object(object)
-            }
-        }
-    }
-}
-
-// From: https://github.com/dtolnay/fastwrite/blob/master/src/lib.rs#L68
-//
-// LLVM is not able to lower `Vec::extend_from_slice` into a memcpy, so this
-// helps eke out that last bit of performance.
-#[inline]
-pub fn extend_from_slice(dst: &mut Vec<u8>, src: &[u8]) {
-    let dst_len = dst.len();
-    let src_len = src.len();
-
-    dst.reserve(src_len);
-
-    unsafe {
-        // We would have failed if `reserve` overflowed
-        dst.set_len(dst_len + src_len);
-
-        ptr::copy_nonoverlapping(
-            src.as_ptr(),
-            dst.as_mut_ptr().offset(dst_len as isize),
-            src_len);
-    }
+    fn write_json(&mut self, json: &Self::Tree) -> io::Result<()>;
 }
@@ -6,16 +6,16 @@
 // The algorithm here was modified from being able to just writing integers,
 // to printing decimal floating points.
 
-use std::{io, mem, ptr, slice};
+use std::{io, ptr, slice};
 
-const DEC_DIGITS_LUT: &'static[u8] =
+const DEC_DIGITS_LUT: &[u8] =
     b"0001020304050607080910111213141516171819\
       2021222324252627282930313233343536373839\
       4041424344454647484950515253545556575859\
       6061626364656667686970717273747576777879\
       8081828384858687888990919293949596979899";
 
-const ZEROFILL: &'static [u8] = &[b'0'; 20];
+const ZEROFILL: &[u8] = &[b'0'; 20];
 
 #[inline(always)]
 unsafe fn write_num(n: &mut u64, curr: &mut isize, buf_ptr: *mut u8, lut_ptr: *const u8) {
@@ -59,7 +59,7 @@ pub unsafe fn write<W: io::Write>(wr: &mut W, positive: bool, mut n: u64, expone
         return wr.write_all(b"0");
     }
 
-    let mut buf: [u8; 30] = mem::uninitialized();
+    let mut buf = [0u8; 30];
     let mut curr = buf.len() as isize;
     let buf_ptr = buf.as_mut_ptr();
     let lut_ptr = DEC_DIGITS_LUT.as_ptr();
@@ -241,6 +241,6 @@ fn safe_abs(x : i16) -> u16 {
     if let Some(y) = x.checked_abs() {
         y as u16
     } else {
-        i16::max_value() as u16 + 1u16
+        i16::MAX as u16 + 1u16
     }
 }
\ No newline at end of file
@@ -1,144 +1,674 @@
 use std::io;
 use std::ptr;
+use std::collections::{HashMap, HashSet};
+use std::str;
 use json::JsonValue;
 use colored::*;
 
-use crate::highlight_color::{HighlightColor, SingleColor, CycledColors};
+use crate::highlight_color::{HighlightColor, HighlightContext, SingleColor, CycledColors};
+use crate::pretty::PrettyConfig;
+use crate::html;
+use crate::style::Style;
+use crate::span::SpanMap;
+use crate::tree::{JsonTree, Node};
+use crate::segment::{self, Line};
+use crate::path::{Path, PathSegment};
+use crate::theme::{Theme, Token};
+use crate::error::{Error, Result};
+use crate::source::{self, SourceChildren, SourceNode};
 
-use crate::generator::codegen::{Generator, extend_from_slice};
+use crate::generator::codegen::Generator;
+
+const OUTPUT_CHUNK_SIZE: usize = 8 * 1024;
 
 #[derive(Debug)]
 enum WriteSlice {
     Remainder(Vec<u8>),
-    Match(Vec<u8>, Color)
+    Match(Vec<u8>, Style),
+    Token(Vec<u8>, Token)
+}
+
+impl WriteSlice {
+    fn new(style: Option<Style>) -> Self {
+        match style {
+          Some(style) => WriteSlice::Match(Vec::with_capacity(1024), style),
+          None => WriteSlice::Remainder(Vec::with_capacity(1024))
+        }
+    }
+
+    fn code(&self) -> &Vec<u8> {
+        match self {
+          WriteSlice::Remainder(code) | WriteSlice::Match(code, _) | WriteSlice::Token(code, _) => code
+        }
+    }
+
+    fn code_mut(&mut self) -> &mut Vec<u8> {
+        match self {
+          WriteSlice::Remainder(code) | WriteSlice::Match(code, _) | WriteSlice::Token(code, _) => code
+        }
+    }
+
+    // An empty slice of the same kind, to carry on writing into
+    fn empty(&self) -> Self {
+        match self {
+          WriteSlice::Remainder(_) => WriteSlice::new(None),
+          WriteSlice::Match(_, style) => WriteSlice::new(Some(*style)),
+          WriteSlice::Token(_, token) => WriteSlice::Token(vec![], *token)
+        }
+    }
 }
 
 impl PartialEq for WriteSlice {
     fn eq(&self, other: &WriteSlice) -> bool {
         match (self, other) {
           (WriteSlice::Remainder(ref left), WriteSlice::Remainder(ref right)) => right == left,
-          (WriteSlice::Match(ref left, ref lcolor), WriteSlice::Match(ref right, ref rcolor)) => right == left && rcolor == lcolor,
+          (WriteSlice::Match(ref left, ref lstyle), WriteSlice::Match(ref right, ref rstyle)) => right == left && rstyle == lstyle,
+          (WriteSlice::Token(ref left, ref ltoken), WriteSlice::Token(ref right, ref rtoken)) => right == left && rtoken == ltoken,
           (_, _) => false
         }
     }
 }
 
-pub struct HighlightGenerator<'a> {
+/// The highlighted output, along with the indices of the requested slices
+/// which weren't found in the JSON object
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightReport {
+    pub output: String,
+    pub unmatched_slices: Vec<usize>
+}
+
+pub struct HighlightGenerator<'a, N: JsonTree = JsonValue> {
     code: Vec<WriteSlice>,
-    slices: Vec<&'a JsonValue>,
-    color: Box<HighlightColor>,
-    remainder_color: Option<Color>
+    slices: Vec<&'a N>,
+    matched: Vec<bool>,
+    color: Box<dyn HighlightColor<N> + 'a>,
+    remainder_style: Option<Style>,
+    pretty: Option<PrettyConfig>,
+    theme: Option<Theme>,
+    dent: u16,
+    path: Path,
+    collapse: Option<usize>,
+    sample: Option<usize>,
+    max_depth: Option<usize>,
+    string_limit: Option<usize>,
+    truncate_matches: bool,
+    highlights: Vec<Highlight>,
+    expanded: HashSet<*const N>,
+    output: Option<Box<dyn io::Write + 'a>>,
+    // Bytes held in every segment but the last, which haven't been written to the output yet
+    buffered: usize
 }
 
-impl<'a> HighlightGenerator<'a> {
+impl<'a, N: JsonTree> HighlightGenerator<'a, N> {
     pub fn new() -> Self {
-        HighlightGenerator {
-            code: vec![],
-            slices: vec![],
-            color: Box::new(SingleColor::new()),
-            remainder_color: None
-        }
+        HighlightGenerator::new_with_colors_and_remainder(None, None)
     }
 
     pub fn new_with_colors(colors: Vec<Color>) -> Self {
-        HighlightGenerator {
-            code: vec![],
-            slices: vec![],
-            color: Box::new(CycledColors::new(colors)),
-            remainder_color: None
-        }
+        HighlightGenerator::new_with_colors_and_remainder(Some(colors), None)
     }
 
     pub fn new_with_colors_and_remainder(colors: Option<Vec<Color>>, remainder_color: Option<Color>) -> Self {
+        HighlightGenerator::new_with_styles_and_remainder(
+          colors.map(|colors| colors.into_iter().map(Style::from).collect()),
+          remainder_color.map(Style::from)
+        )
+    }
+
+    pub fn new_with_styles(styles: Vec<Style>) -> Self {
+        HighlightGenerator::new_with_styles_and_remainder(Some(styles), None)
+    }
+
+    pub fn new_with_styles_and_remainder(styles: Option<Vec<Style>>, remainder_style: Option<Style>) -> Self {
         HighlightGenerator {
             code: vec![],
             slices: vec![],
-            color: match colors {
-              Some(colors) => Box::new(CycledColors::new(colors)),
+            matched: vec![],
+            color: match styles {
+              Some(styles) => Box::new(CycledColors::new(styles)),
               None => Box::new(SingleColor::new())
             },
-            remainder_color
+            remainder_style,
+            pretty: None,
+            theme: None,
+            dent: 0,
+            path: Path::root(),
+            collapse: None,
+            sample: None,
+            max_depth: None,
+            string_limit: None,
+            truncate_matches: false,
+            highlights: vec![],
+            expanded: HashSet::new(),
+            output: None,
+            buffered: 0
         }
     }
 
+    pub fn with_pretty(mut self, pretty: PrettyConfig) -> Self {
+        self.pretty = Some(pretty);
+        self
+    }
+
+    /// Styles the highlighted slices with a custom strategy, which is given the context of every match
+    pub fn with_highlight_color<C: HighlightColor<N> + 'a>(mut self, color: C) -> Self {
+        self.color = Box::new(color);
+        self
+    }
+
+    /// Colors every token of the JSON by its kind, underneath the highlighted slices
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Collapses subtrees without any highlighted node, such as `{…12 keys}`, keeping
+    /// `context` siblings on either side of every expanded node
+    pub fn with_collapse(mut self, context: usize) -> Self {
+        self.collapse = Some(context);
+        self
+    }
+
+    /// Writes only the first and last `items` of longer arrays, along with every item
+    /// containing a highlighted node, such as `[1, 2, … 4,812 more …, 4815, 4816]`
+    pub fn with_array_sampling(mut self, items: usize) -> Self {
+        self.sample = Some(items);
+        self
+    }
+
+    /// Shows objects and arrays nested deeper than `depth` as `{…}` or `[…]`,
+    /// except for the highlighted nodes and their ancestors, which are expanded fully
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Truncates string values longer than `chars` characters, such as `"aGVsbG8…"`.
+    /// Strings which are highlighted themselves are kept whole, unless `with_truncated_matches` is used.
+    pub fn with_string_limit(mut self, chars: usize) -> Self {
+        self.string_limit = Some(chars);
+        self
+    }
+
+    pub fn with_truncated_matches(mut self) -> Self {
+        self.truncate_matches = true;
+        self
+    }
+
+    /// Writes the highlighted JSON into the output as it is generated,
+    /// rather than holding on to it until it is consumed
+    pub fn with_output<W: io::Write + 'a>(mut self, output: W) -> Self {
+        self.output = Some(Box::new(output));
+        self
+    }
+
     pub fn consume(&mut self) -> String {
-        let slices : Vec<String> = self.code.iter()
-            .map(|slice| match (slice, self.remainder_color) {
+        self.styled_slices().iter()
+            .map(|(code, style)| match style {
+                Some(style) => style.paint(code).to_string(),
+                None => code.to_string()
+            })
+            .collect()
+    }
+
+    pub fn consume_html(&mut self) -> String {
+        self.styled_slices().iter()
+            .map(|(code, style)| match style {
+                Some(style) => html::span(code, style),
+                None => html::escape(code)
+            })
+            .collect()
+    }
+
+    pub fn consume_html_page(&mut self) -> String {
+        let styles : Vec<Style> = self.styled_slices().iter()
+            .filter_map(|(_, style)| *style)
+            .collect();
+        html::page(&self.consume_html(), &styles)
+    }
+
+    /// The rendered lines, each a list of styled segments, for terminal UI libraries
+    /// to wrap and scroll by themselves
+    pub fn consume_lines(&mut self) -> Vec<Line> {
+        segment::lines(self.styled_slices())
+    }
+
+    /// The plain JSON text, along with where each highlighted slice was written in it.
+    /// Highlights which were streamed into an output aren't mapped.
+    pub fn consume_spans(&mut self) -> SpanMap {
+        let mut segment_starts = Vec::with_capacity(self.code.len());
+        let mut text = String::new();
+        for slice in &self.code {
+            segment_starts.push(text.len());
+            text.push_str(unsafe { str::from_utf8_unchecked(slice.code()) });
+        }
+        let offset = |(segment, offset): Mark| segment_starts.get(segment).map_or(offset, |start| start + offset);
+        let highlights = self.highlights.iter()
+            .map(|highlight| (offset(highlight.start)..offset(highlight.end), highlight.slice_index, highlight.style))
+            .collect();
+        SpanMap::new(text, highlights)
+    }
+
+    fn styled_slices(&self) -> Vec<(&str, Option<Style>)> {
+        self.code.iter()
+            .filter(|slice| !slice.code().is_empty())
+            .map(|slice| match (slice, self.remainder_style) {
                 // Original strings were unicode, numbers are all ASCII,
                 // therefore this is safe.
-                (WriteSlice::Match(code, ref color),_) | (WriteSlice::Remainder(code), Some(ref color)) => {
-                    unsafe { String::from_utf8_unchecked(code.to_vec()).color(*color).to_string() }
+                (WriteSlice::Match(code, ref style), _) => {
+                    (unsafe { str::from_utf8_unchecked(code) }, Some(*style))
                 },
-                (WriteSlice::Remainder(code), None) => {
-                    unsafe { String::from_utf8_unchecked(code.to_vec()) }
+                (WriteSlice::Remainder(code), style) => {
+                    (unsafe { str::from_utf8_unchecked(code) }, style)
+                },
+                (WriteSlice::Token(code, token), style) => {
+                    let token_style = self.theme.and_then(|theme| theme.get(*token));
+                    (unsafe { str::from_utf8_unchecked(code) }, token_style.or(style))
                 }
             })
-            .collect();
-        slices.join("")
+            .collect()
     }
 
-    pub fn write_json_with_highlight(&mut self, json: &JsonValue, slices: &mut Vec<&'a JsonValue>) -> io::Result<()> {
+    pub fn write_json_with_highlight(&mut self, json: &N, slices: &mut Vec<&'a N>) -> io::Result<()> {
+      self.matched.resize(self.slices.len() + slices.len(), false);
       self.slices.append(slices);
-      self.write_json(json)
+      if self.collapse.is_some() || self.sample.is_some() || self.max_depth.is_some() {
+        self.expand_matches(json);
+      }
+      self.write_json(json)?;
+      self.finish()
+    }
+
+    /// Like `write_json_with_highlight`, but fails if any of the slices wasn't found in the JSON object
+    pub fn write_json_with_highlight_strict(&mut self, json: &N, slices: &mut Vec<&'a N>) -> Result<()> {
+      self.write_json_with_highlight(json, slices)?;
+      let unmatched_slices = self.unmatched_slices();
+      if unmatched_slices.is_empty() {
+        Ok(())
+      } else {
+        Err(Error::UnmatchedSlices(unmatched_slices))
+      }
+    }
+
+    /// Writes the source text the JSON object was parsed from exactly as it was written,
+    /// highlighting the slices where they appear in it. The object's layout, theme and
+    /// abbreviations don't apply, as the source is never reformatted.
+    pub fn write_source_with_highlight(&mut self, source: &str, json: &N, slices: &mut Vec<&'a N>) -> Result<()> {
+      let root = source::parse(source)?;
+      self.matched.resize(self.slices.len() + slices.len(), false);
+      self.slices.append(slices);
+      let mut written = 0;
+      self.write_source_node(source, &mut written, json, &root)?;
+      self.write(&source.as_bytes()[written..])?;
+      self.finish()?;
+      Ok(())
+    }
+
+    /// Indices of the slices which weren't found in the JSON objects written so far
+    pub fn unmatched_slices(&self) -> Vec<usize> {
+      self.matched.iter()
+        .enumerate()
+        .filter(|(_, matched)| !**matched)
+        .map(|(index, _)| index)
+        .collect()
+    }
+
+    pub fn report(&mut self) -> HighlightReport {
+      HighlightReport {
+        output: self.consume(),
+        unmatched_slices: self.unmatched_slices()
+      }
+    }
+
+    fn match_index(&mut self, json: &N) -> Option<usize> {
+      let mut match_index = None;
+      for (index, slice) in self.slices.iter().enumerate() {
+        if ptr::eq(json, *slice) {
+          self.matched[index] = true;
+          match_index = match_index.or(Some(index));
+        }
+      }
+      match_index
+    }
+
+    pub(crate) fn finish(&mut self) -> io::Result<()> {
+      self.flush_output()?;
+      match self.output {
+        Some(ref mut output) => output.flush(),
+        None => Ok(())
+      }
+    }
+
+    pub(crate) fn write_highlighted<F>(&mut self, style: Style, write: F) -> io::Result<()>
+      where F: FnOnce(&mut Self) -> io::Result<()> {
+      let current_style = self.current_style();
+      self.segment(Some(style))?;
+      write(self)?;
+      self.segment(current_style)
+    }
+
+    // Writes out the segments generated so far, keeping an empty segment
+    // in the current color to carry on writing into
+    fn flush_output(&mut self) -> io::Result<()> {
+      if self.output.is_none() {
+        return Ok(());
+      }
+      let code = self.consume();
+      let current = self.code.last().map(WriteSlice::empty);
+      self.code.clear();
+      self.code.extend(current);
+      self.buffered = 0;
+      match self.output {
+        Some(ref mut output) => output.write_all(code.as_bytes()),
+        None => Ok(())
+      }
+    }
+
+    fn flush_output_if_full(&mut self) -> io::Result<()> {
+      match self.code.last() {
+        Some(slice) if self.buffered + slice.code().len() >= OUTPUT_CHUNK_SIZE => self.flush_output(),
+        _ => Ok(())
+      }
+    }
+
+    // Segments are only written out once enough of them have been buffered,
+    // rather than every time a token or highlight starts
+    fn push_segment(&mut self, slice: WriteSlice) {
+      self.buffered += self.code.last().map_or(0, |last| last.code().len());
+      self.code.push(slice);
     }
 
-    fn current_color(&self) -> Option<Color> {
+    fn current_style(&self) -> Option<Style> {
       match self.code.last() {
-        Some(WriteSlice::Match(_, ref color)) => Some(color.clone()),
+        Some(WriteSlice::Match(_, ref style)) => Some(*style),
         _ => None
       }
     }
 
-    fn segment(&mut self, color : Option<Color>) {
-      self.code.push(
-        match color {
-          Some(color) => WriteSlice::Match(Vec::with_capacity(1024), color),
-          None => WriteSlice::Remainder(Vec::with_capacity(1024))
+    fn segment(&mut self, style : Option<Style>) -> io::Result<()> {
+      self.push_segment(WriteSlice::new(style));
+      Ok(())
+    }
+
+    fn match_segment(&mut self, json: &N, slice_index: usize) -> io::Result<Style> {
+      let style = self.get_style(json, slice_index);
+      self.segment(Some(style))?;
+      Ok(style)
+    }
+
+    // The segment being written, and how far into it
+    fn mark(&self) -> Mark {
+      match self.code.last() {
+        Some(last) => (self.code.len() - 1, last.code().len()),
+        None => (0, 0)
+      }
+    }
+
+    fn get_style(&mut self, json: &N, slice_index: usize) -> Style {
+      let context = HighlightContext {
+        path: &self.path,
+        depth: self.path.depth(),
+        value: json,
+        slice_index
+      };
+      self.color.get_style(&context)
+    }
+
+    // Writes the source up to the end of the node, `written` being how far into it has been written
+    fn write_source_node(&mut self, source: &str, written: &mut usize, json: &N, located: &SourceNode) -> io::Result<()> {
+      let current_style = self.current_style();
+      let highlight = match self.match_index(json) {
+        Some(slice_index) => {
+          self.write(&source.as_bytes()[*written..located.range.start])?;
+          *written = located.range.start;
+          Some((self.match_segment(json, slice_index)?, slice_index, self.mark()))
+        },
+        None => None
+      };
+
+      // Children are written in the order they appear in the source,
+      // which may differ from the order of the object's entries
+      match (json.node(), &located.children) {
+        (Node::Array, SourceChildren::Array(located_items)) => {
+          for (index, (item, located_item)) in json.items().zip(located_items).enumerate() {
+            self.write_source_child(source, written, PathSegment::Index(index), item, located_item)?;
+          }
+        },
+        (Node::Object, SourceChildren::Object(located_members)) => {
+          // A key written more than once takes its last value, as when parsing
+          let members : HashMap<&str, &SourceNode> = located_members.iter()
+            .map(|(key, member)| (key.as_str(), member))
+            .collect();
+          let mut entries : Vec<(&str, &N, &SourceNode)> = json.entries()
+            .filter_map(|(key, value)| members.get(key).map(|member| (key, value, *member)))
+            .collect();
+          entries.sort_by_key(|(_, _, member)| member.range.start);
+          for (key, value, member) in entries {
+            self.write_source_child(source, written, PathSegment::Key(key.to_string()), value, member)?;
+          }
+        },
+        _ => {}
+      }
+
+      if let Some((style, slice_index, start)) = highlight {
+        self.write(&source.as_bytes()[*written..located.range.end])?;
+        *written = located.range.end;
+        if self.output.is_none() {
+          self.highlights.push(Highlight { start, end: self.mark(), slice_index, style });
         }
-      );
+        self.segment(current_style)?;
+      }
+      Ok(())
+    }
+
+    fn write_source_child(&mut self, source: &str, written: &mut usize, segment: PathSegment, json: &N, located: &SourceNode) -> io::Result<()> {
+      self.path.push(segment);
+      let written_child = self.write_source_node(source, written, json, located);
+      self.path.pop();
+      written_child
     }
 
-    fn match_segment(&mut self) {
-      let color = self.get_color();
-      self.segment(Some(color));
+    // Marks the nodes which are highlighted or have a highlighted descendant,
+    // returning whether the node is one of them
+    fn expand_matches(&mut self, json: &N) -> bool {
+      // Every child is visited, rather than stopping at the first match
+      let children_match = match json.node() {
+        Node::Object => json.entries().fold(false, |found, (_, value)| self.expand_matches(value) | found),
+        Node::Array => json.items().fold(false, |found, item| self.expand_matches(item) | found),
+        _ => false
+      };
+      let is_match = children_match || self.slices.iter().any(|slice| ptr::eq(json, *slice));
+      if is_match {
+        self.expanded.insert(json);
+      }
+      is_match
     }
 
-    fn get_color(&mut self) -> Color {
-      self.color.get_color()
+    fn contains_match(&self, json: &N) -> bool {
+      self.expanded.contains(&(json as *const N))
     }
 
-    fn write_array(&mut self, array: &Vec<JsonValue>) -> io::Result<()> {
-        self.write_char(b'[')?;
-        let mut iter = array.iter();
+    fn is_too_deep(&self) -> bool {
+      self.max_depth.is_some_and(|max_depth| self.path.depth() > max_depth)
+    }
 
-        if let Some(item) = iter.next() {
-            self.write_json(item)?;
+    fn is_expanded(&self, json: &N) -> bool {
+      if self.current_style().is_some() || self.contains_match(json) {
+        return true;
+      }
+      self.collapse.is_none() && !self.is_too_deep()
+    }
+
+    // Subtrees beyond the maximum depth are written without counting their children
+    fn write_collapsed(&mut self, json: &N, node: Node) -> io::Result<()> {
+      let collapsed = match node {
+        Node::Object if self.is_too_deep() => "{…}".to_string(),
+        Node::Array if self.is_too_deep() => "[…]".to_string(),
+        Node::Object => format!("{{…{}}}", plural(json.entries().count(), "key", "keys")),
+        Node::Array => format!("[…{}]", plural(json.items().count(), "item", "items")),
+        _ => unreachable!()
+      };
+      self.write_token(Token::Punctuation, collapsed.as_bytes())
+    }
+
+    // Which children to write, when collapsing or sampling hides some of them.
+    // Children containing a highlighted node are never hidden.
+    fn visibility<'n, I>(&self, children: I, sample: Option<usize>) -> Option<Visibility>
+      where I: Iterator<Item = &'n N>, N: 'n {
+      let context = self.collapse.filter(|_| self.current_style().is_none());
+      if context.is_none() && sample.is_none() {
+        return None;
+      }
+      let mut len = 0;
+      let mut matches = vec![];
+      for (index, child) in children.enumerate() {
+        len += 1;
+        if self.contains_match(child) {
+          matches.push(index);
+        }
+      }
+      let sample = sample.filter(|sample| len > sample.saturating_mul(2));
+      if context.is_none() && sample.is_none() {
+        return None;
+      }
+      Some(Visibility { len, matches, context, sample })
+    }
+
+    fn write_container<T, I, F>(&mut self, open: u8, close: u8, children: I, visibility: Option<Visibility>, mut write_item: F) -> io::Result<()>
+      where I: Iterator<Item = T>, F: FnMut(&mut Self, usize, T) -> io::Result<()> {
+        self.write_punctuation(open)?;
+        let mut written = 0;
+        let mut hidden = 0;
+        for (index, child) in children.enumerate() {
+            if visibility.as_ref().is_some_and(|visibility| !visibility.shows(index)) {
+                hidden += 1;
+                continue;
+            }
+            if hidden > 0 {
+                self.write_gap(&mut written, hidden)?;
+                hidden = 0;
+            }
+            self.begin_child(&mut written)?;
+            write_item(self, index, child)?;
+        }
+        if hidden > 0 {
+            self.write_gap(&mut written, hidden)?;
+        }
+        if written == 0 {
+            return self.write_punctuation(close);
+        }
+
+        self.dedent();
+        self.new_line()?;
+        self.write_punctuation(close)
+    }
+
+    // Starts a child or a gap, separating it from the one written before
+    fn begin_child(&mut self, written: &mut usize) -> io::Result<()> {
+        if *written == 0 {
+            self.indent();
         } else {
-            self.write_char(b']')?;
-            return Ok(());
+            self.write_punctuation(b',')?;
         }
+        *written += 1;
+        self.new_line()
+    }
+
+    fn write_gap(&mut self, written: &mut usize, hidden: usize) -> io::Result<()> {
+        self.begin_child(written)?;
+        self.write_token(Token::Punctuation, format!("… {} more …", thousands(hidden)).as_bytes())
+    }
 
-        for item in iter {
-            self.write_char(b',')?;
-            self.write_json(item)?;
+    // Truncates at a character boundary, before escaping, so neither
+    // a character nor an escape sequence is ever cut in half
+    fn write_string_value(&mut self, string: &str, is_match: bool) -> io::Result<()> {
+        let limit = match self.string_limit {
+            Some(limit) if !is_match || self.truncate_matches => limit,
+            _ => return self.write_string_token(Token::String, string)
+        };
+        match string.char_indices().nth(limit) {
+            Some((end, _)) => self.write_string_token(Token::String, &format!("{}…", &string[..end])),
+            None => self.write_string_token(Token::String, string)
         }
+    }
+}
+
+// A segment index, and a byte offset within that segment
+type Mark = (usize, usize);
+
+// Where a highlighted slice starts and ends within the segments
+#[derive(Debug)]
+struct Highlight {
+    start: Mark,
+    end: Mark,
+    slice_index: usize,
+    style: Style
+}
 
-        self.write_char(b']')
+// Which children of an object or array are written: those near a highlighted
+// child when collapsing, and those at either end when sampling
+struct Visibility {
+    len: usize,
+    matches: Vec<usize>,
+    context: Option<usize>,
+    sample: Option<usize>
+}
+
+impl Visibility {
+    fn shows(&self, index: usize) -> bool {
+        let is_match = self.matches.binary_search(&index).is_ok();
+        let near_match = match self.context {
+            Some(context) => self.matches.iter().any(|&matched| index.abs_diff(matched) <= context),
+            None => true
+        };
+        let sampled = match self.sample {
+            Some(sample) => is_match || index < sample || index >= self.len - sample,
+            None => true
+        };
+        near_match && sampled
     }
 }
 
-impl<'a> Generator for HighlightGenerator<'a> {
+fn plural(count: usize, one: &str, many: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, one)
+    } else {
+        format!("{} {}", thousands(count), many)
+    }
+}
+
+// Writes out a count with thousands separators, such as 4,812
+fn thousands(count: usize) -> String {
+    let digits = count.to_string();
+    let mut separated = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            separated.push(',');
+        }
+        separated.push(digit);
+    }
+    separated
+}
+
+impl<'a, N: JsonTree> Default for HighlightGenerator<'a, N> {
+    fn default() -> Self {
+        HighlightGenerator::new()
+    }
+}
+
+impl<'a, N: JsonTree> Generator for HighlightGenerator<'a, N> {
     type T = Vec<u8>;
+    type Tree = N;
 
     fn write(&mut self, slice: &[u8]) -> io::Result<()> {
-        extend_from_slice(&mut self.get_writer(), slice);
-        Ok(())
+        self.get_writer().extend_from_slice(slice);
+        self.flush_output_if_full()
     }
 
     #[inline(always)]
     fn write_char(&mut self, ch: u8) -> io::Result<()> {
         self.get_writer().push(ch);
-        Ok(())
+        self.flush_output_if_full()
     }
 
     #[inline(always)]
@@ -146,43 +676,105 @@ impl<'a> Generator for HighlightGenerator<'a> {
         if self.code.is_empty() {
           self.code.push(WriteSlice::Remainder(Vec::with_capacity(1024)));
         };
-        match self.code.last_mut() {
-            Some(WriteSlice::Remainder(ref mut code)) => code,
-            Some(WriteSlice::Match(ref mut code, _)) => code,
-            None => panic!("Internal Error: No writer")
-        }
+        let last = self.code.len() - 1;
+        self.code[last].code_mut()
     }
 
     #[inline(always)]
-    fn write_min(&mut self, _: &[u8], min: u8) -> io::Result<()> {
-        self.get_writer().push(min);
+    fn write_min(&mut self, slice: &[u8], min: u8) -> io::Result<()> {
+        match self.pretty {
+            Some(PrettyConfig { space_after_colon: true, .. }) => self.write(slice),
+            _ => self.write_char(min)
+        }
+    }
+
+    fn new_line(&mut self) -> io::Result<()> {
+        if let Some(pretty) = self.pretty {
+            self.write(pretty.line_ending())?;
+            self.write(&pretty.indentation(self.dent))?;
+        }
         Ok(())
     }
 
-    fn write_json(&mut self, json: &JsonValue) -> io::Result<()> {
-        let match_index = self.slices.iter().position(|&slice|ptr::eq(json, slice));
-        let current_color = self.current_color();
-        if let Some(_) = match_index {
-            self.match_segment();
+    fn indent(&mut self) {
+        self.dent += 1;
+    }
+
+    // Tokens inside a highlighted slice are written in the slice's color
+    fn begin_token(&mut self, token: Token) -> io::Result<()> {
+        if self.theme.is_none() || self.current_style().is_some() {
+            return Ok(());
+        }
+        self.push_segment(WriteSlice::Token(vec![], token));
+        Ok(())
+    }
+
+    fn end_token(&mut self) -> io::Result<()> {
+        if let Some(WriteSlice::Token(_, _)) = self.code.last() {
+            self.push_segment(WriteSlice::Remainder(vec![]));
+        }
+        Ok(())
+    }
+
+    fn dedent(&mut self) {
+        self.dent -= 1;
+    }
+
+    fn write_object(&mut self, json: &N) -> io::Result<()> {
+        let visibility = self.visibility(json.entries().map(|(_, value)| value), None);
+        self.write_container(b'{', b'}', json.entries(), visibility, |gen, _, (key, value)| {
+            gen.write_string_token(Token::Key, key)?;
+            gen.write_colon()?;
+            gen.path.push(PathSegment::Key(key.to_string()));
+            let written = gen.write_json(value);
+            gen.path.pop();
+            written
+        })
+    }
+
+    fn write_array(&mut self, json: &N) -> io::Result<()> {
+        let visibility = self.visibility(json.items(), self.sample);
+        self.write_container(b'[', b']', json.items(), visibility, |gen, index, item| {
+            gen.path.push(PathSegment::Index(index));
+            let written = gen.write_json(item);
+            gen.path.pop();
+            written
+        })
+    }
+
+    fn write_json(&mut self, json: &N) -> io::Result<()> {
+        let node = json.node();
+        let is_empty = match node {
+            Node::Object => json.entries().next().is_none(),
+            Node::Array => json.items().next().is_none(),
+            _ => true
+        };
+        if !is_empty && !self.is_expanded(json) {
+            return self.write_collapsed(json, node);
+        }
+
+        let match_index = self.match_index(json);
+        let current_style = self.current_style();
+        let highlight = match match_index {
+            Some(slice_index) => Some((self.match_segment(json, slice_index)?, slice_index, self.mark())),
+            None => None
         };
 
-        let inner_io = match *json {
-            JsonValue::Null               => self.write(b"null"),
-            JsonValue::Short(ref short)   => self.write_string(short.as_str()),
-            JsonValue::String(ref string) => self.write_string(string),
-            JsonValue::Number(ref number) => self.write_number(number),
-            JsonValue::Boolean(true)      => self.write(b"true"),
-            JsonValue::Boolean(false)     => self.write(b"false"),
-            JsonValue::Array(ref array)   => {
-                self.write_array(array)
-            },
-            JsonValue::Object(ref object) => {
-                self.write_object(object)
-            }
+        let inner_io = match node {
+            Node::Null               => self.write_token(Token::Null, b"null"),
+            Node::String(string)     => self.write_string_value(string, match_index.is_some()),
+            Node::Number             => self.write_number_token(json),
+            Node::Boolean(true)      => self.write_token(Token::Boolean, b"true"),
+            Node::Boolean(false)     => self.write_token(Token::Boolean, b"false"),
+            Node::Array              => self.write_array(json),
+            Node::Object             => self.write_object(json)
         };
         
-        if let Some(_) = match_index {
-            self.segment(current_color);
+        if let Some((style, slice_index, start)) = highlight {
+            if self.output.is_none() {
+                self.highlights.push(Highlight { start, end: self.mark(), slice_index, style });
+            }
+            self.segment(current_style)?;
         };
         inner_io
     }
@@ -192,6 +784,8 @@ impl<'a> Generator for HighlightGenerator<'a> {
 mod tests {
   use super::*;
   use json::*;
+  use crate::pretty::{Indent, LineEnding};
+  use crate::error::Error;
 
   #[test]
   fn should_implement_basic_json_dump() {
@@ -257,7 +851,7 @@ mod tests {
       WriteSlice::Match(
         "[null,\"world\",true]"
         .as_bytes().to_vec(),
-        Color::Red
+        Color::Red.into()
       )
     );
     assert_eq!(
@@ -292,7 +886,7 @@ mod tests {
       WriteSlice::Match(
         "{\"foo\":false,\"bar\":null,\"answer\":42,\"list\":[null,\"world\",true]}"
         .as_bytes().to_vec(),
-        Color::Red
+        Color::Red.into()
       )
     );
   }
@@ -453,4 +1047,565 @@ mod tests {
       )      
     );
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn should_give_highlight_colors_the_context_of_each_match() {
+      let input = object!{
+        "events" => array![
+          object!{ "severity" => "error" },
+          object!{ "severity" => "warning" }
+        ]
+      };
+
+      let mut slices = vec![
+        &input["events"][1]["severity"],
+        &input["events"][0]["severity"]
+      ];
+
+      let mut contexts = vec![];
+      let mut gen = HighlightGenerator::new().with_highlight_color(|context: &HighlightContext| {
+        contexts.push((context.path.to_string(), context.depth, context.slice_index));
+        match context.value.as_str() {
+          Some("error") => Color::Red.into(),
+          _ => Color::Yellow.into()
+        }
+      });
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}{}{}",
+        r#"{"events":[{"severity":"#,
+        r#""error""#.red(),
+        r#"},{"severity":"#,
+        r#""warning""#.yellow(),
+        r#"}]}"#
+      )
+    );
+    drop(gen);
+    assert_eq!(contexts, vec![
+      ("/events/0/severity".to_string(), 3, 1),
+      ("/events/1/severity".to_string(), 3, 0)
+    ]);
+  }
+
+  #[test]
+  fn should_collapse_subtrees_without_matches() {
+      let input = object!{
+        "meta" => object!{ "page" => 1, "total" => 3 },
+        "items" => array![
+          object!{ "id" => 1, "tags" => array!["a"] },
+          object!{ "id" => 2, "tags" => array![] },
+          object!{ "id" => 3, "tags" => array!["b", "c"] },
+          object!{ "id" => 4, "tags" => array!["d"] }
+        ],
+        "status" => "ok"
+      };
+
+      let mut slices = vec![
+        &input["items"][2]["id"]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_collapse(1);
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        r#"{"meta":{…2 keys},"items":[… 1 more …,{…2 keys},{"id":"#,
+        "3".red(),
+        r#","tags":[…2 items]},{…2 keys}],"status":"ok"}"#
+      )
+    );
+  }
+
+  #[test]
+  fn should_keep_highlighted_subtrees_expanded_when_collapsing() {
+      let input = object!{
+        "a" => 1,
+        "b" => 2,
+        "c" => object!{ "d" => array![1, 2] },
+        "e" => 3,
+        "f" => 4
+      };
+
+      let mut slices = vec![
+        &input["c"]
+      ];
+
+      let mut gen = HighlightGenerator::new()
+        .with_collapse(0)
+        .with_pretty(PrettyConfig::default());
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        "{\n  … 2 more …,\n  \"c\": ",
+        "{\n    \"d\": [\n      1,\n      2\n    ]\n  }".red(),
+        ",\n  … 2 more …\n}"
+      )
+    );
+  }
+
+  #[test]
+  fn should_sample_long_arrays_around_matches() {
+      let input = object!{
+        "ids" => JsonValue::Array((0..5000).map(JsonValue::from).collect()),
+        "short" => array![1, 2, 3, 4]
+      };
+
+      let mut slices = vec![
+        &input["ids"][2500]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_array_sampling(2);
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        r#"{"ids":[0,1,… 2,498 more …,"#,
+        "2500".red(),
+        r#",… 2,497 more …,4998,4999],"short":[1,2,3,4]}"#
+      )
+    );
+  }
+
+  #[test]
+  fn should_truncate_long_strings_except_matches() {
+      let input = object!{
+        "blob" => "aGVsbG8gd29ybGQ=",
+        "quote" => "\"é\"\n\"ü\"",
+        "short" => "abc",
+        "match" => "0123456789"
+      };
+
+      let mut slices = vec![
+        &input["match"]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_string_limit(4);
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        r#"{"blob":"aGVs…","quote":"\"é\"\n…","short":"abc","match":"#,
+        r#""0123456789""#.red(),
+        "}"
+      )
+    );
+
+      let mut gen = HighlightGenerator::new().with_string_limit(4).with_truncated_matches();
+
+      gen.write_json_with_highlight(
+        &input, &mut vec![&input["match"]]
+      ).expect("Can't fail");
+
+    assert!(gen.consume().contains(&r#""0123…""#.red().to_string()));
+  }
+
+  #[test]
+  fn should_limit_depth_except_towards_matches() {
+      let input = object!{
+        "config" => object!{ "db" => object!{ "host" => "localhost" }, "ports" => array![80, 443] },
+        "events" => array![
+          object!{ "level" => "info", "context" => object!{ "user" => 1 } },
+          object!{ "level" => "error", "context" => object!{ "user" => 2, "trace" => array!["a"] } }
+        ]
+      };
+
+      let mut slices = vec![
+        &input["events"][1]["context"]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_max_depth(1);
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        r#"{"config":{"db":{…},"ports":[…]},"events":[{…},{"level":"error","context":"#,
+        r#"{"user":2,"trace":["a"]}"#.red(),
+        "}]}"
+      )
+    );
+  }
+
+  #[test]
+  fn should_map_spans_of_highlights_in_plain_text() {
+      let input = object!{
+        "code" => 200,
+        "payload" => object!{ "features" => array!["awesome", "easyAPI"] }
+      };
+
+      let mut slices = vec![
+        &input["payload"]["features"][1],
+        &input["payload"]
+      ];
+
+      let mut gen = HighlightGenerator::new_with_colors(vec![Color::Red, Color::Green])
+        .with_pretty(PrettyConfig::default());
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    let map = gen.consume_spans();
+    let texts : Vec<&str> = map.spans.iter().map(|span| &map.text[span.range.clone()]).collect();
+
+    assert_eq!(map.text, input.pretty(2));
+    assert_eq!(texts, vec![&input["payload"].pretty(2).replace('\n', "\n  "), r#""easyAPI""#]);
+    assert_eq!(map.spans.iter().map(|span| span.slice_index).collect::<Vec<_>>(), vec![1, 0]);
+    assert_eq!(map.spans[1].style, Color::Green.into());
+    assert_eq!((map.spans[1].start.line, map.spans[1].start.column), (5, 6));
+    assert_eq!((map.spans[0].start.line, map.spans[0].end.line), (2, 7));
+  }
+
+  #[test]
+  fn should_highlight_source_as_written() {
+    let source = "{\n  \"payload\": { \"ratio\" : 1.50E+2, \"tags\": [\"a\"] },\n  \"code\": 200\n}\n";
+    let input = json::parse(source).unwrap();
+    let mut slices = vec![&input["payload"]["tags"][0], &input["payload"], &input["code"]];
+
+    let mut gen = HighlightGenerator::new_with_colors(vec![Color::Red, Color::Green, Color::Blue]);
+    gen.write_source_with_highlight(source, &input, &mut slices).expect("Can't fail");
+    let map = gen.consume_spans();
+    let texts : Vec<&str> = map.spans.iter().map(|span| &map.text[span.range.clone()]).collect();
+
+    assert_eq!(map.text, source);
+    assert_eq!(texts, vec![r#"{ "ratio" : 1.50E+2, "tags": ["a"] }"#, r#""a""#, "200"]);
+    assert_eq!((map.spans[2].start.line, map.spans[2].start.column), (2, 10));
+
+    let mut gen = HighlightGenerator::new_with_colors(vec![Color::Red, Color::Green]);
+    gen.write_source_with_highlight(source, &input, &mut vec![&input["payload"]["tags"][0], &input["payload"]]).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}{}{}",
+        "{\n  \"payload\": ",
+        r#"{ "ratio" : 1.50E+2, "tags": ["#.green(),
+        r#""a""#.red(),
+        "] }".green(),
+        ",\n  \"code\": 200\n}\n"
+      )
+    );
+    assert!(matches!(
+      HighlightGenerator::new().write_source_with_highlight("[1,]", &input, &mut vec![]),
+      Err(Error::InvalidSource(json::Error::UnexpectedCharacter { ch: ']', line: 1, column: 4 }))
+    ));
+  }
+
+  #[test]
+  fn should_split_output_into_lines_of_styled_segments() {
+      let input = object!{
+        "code" => 200,
+        "tags" => array!["a"]
+      };
+
+      let mut slices = vec![
+        &input["tags"]
+      ];
+
+      let mut gen = HighlightGenerator::new()
+        .with_pretty(PrettyConfig::default())
+        .with_theme(Theme { key: Some(Color::Blue.into()), ..Theme::new() });
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    let lines : Vec<Vec<(String, Style)>> = gen.consume_lines().into_iter()
+      .map(|line| line.into_iter().map(|segment| (segment.text, segment.style)).collect())
+      .collect();
+
+    assert_eq!(lines, vec![
+      vec![("{".to_string(), Style::default())],
+      vec![("  ".to_string(), Style::default()), (r#""code""#.to_string(), Color::Blue.into()), (": 200,".to_string(), Style::default())],
+      vec![("  ".to_string(), Style::default()), (r#""tags""#.to_string(), Color::Blue.into()), (": ".to_string(), Style::default()), ("[".to_string(), Color::Red.into())],
+      vec![(r#"    "a""#.to_string(), Color::Red.into())],
+      vec![("  ]".to_string(), Color::Red.into())],
+      vec![("}".to_string(), Style::default())]
+    ]);
+  }
+
+  #[test]
+  fn should_separate_thousands() {
+    assert_eq!(thousands(0), "0");
+    assert_eq!(thousands(999), "999");
+    assert_eq!(thousands(4812), "4,812");
+    assert_eq!(thousands(1234567), "1,234,567");
+  }
+
+  #[test]
+  fn should_pretty_print_with_highlights_spanning_lines() {
+      let input = object!{
+        "foo" => false,
+        "list" => array![json::Null, "world"],
+        "empty" => array![]
+      };
+
+      let mut slices = vec![
+        &input["list"]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_pretty(PrettyConfig::with_spaces(2));
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}",
+        "{\n  \"foo\": false,\n  \"list\": ",
+        "[\n    null,\n    \"world\"\n  ]".red(),
+        ",\n  \"empty\": []\n}"
+      )
+    );
+  }
+
+  #[test]
+  fn should_pretty_print_with_tabs_and_crlf() {
+      let input = object!{
+        "foo" => array![1, 2]
+      };
+
+      let mut gen = HighlightGenerator::new().with_pretty(PrettyConfig {
+        indent: Indent::Tabs,
+        line_ending: LineEnding::CrLf,
+        space_after_colon: false
+      });
+
+      gen.write_json(
+        &input
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      "{\r\n\t\"foo\":[\r\n\t\t1,\r\n\t\t2\r\n\t]\r\n}"
+    );
+  }
+
+  #[test]
+  fn should_render_matches_as_html_spans() {
+      let input = object!{
+        "name" => "<b>",
+        "list" => array![json::Null, "world"]
+      };
+
+      let mut slices = vec![
+        &input["name"],
+        &input["list"]
+      ];
+
+      let mut gen = HighlightGenerator::new_with_colors_and_remainder(Some(vec![Color::Red, Color::Green]), Some(Color::White));
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume_html(),
+      concat!(
+        r#"<span class="jhw-white">{&quot;name&quot;:</span>"#,
+        r#"<span class="jhw-red">&quot;&lt;b&gt;&quot;</span>"#,
+        r#"<span class="jhw-white">,&quot;list&quot;:</span>"#,
+        r#"<span class="jhw-green">[null,&quot;world&quot;]</span>"#,
+        r#"<span class="jhw-white">}</span>"#
+      )
+    );
+    assert!(gen.consume_html_page().contains(".jhw .jhw-green { color: #0dbc79; }"));
+  }
+
+  #[test]
+  fn should_stream_highlights_into_output() {
+      let input = object!{
+        "foo" => false,
+        "list" => array![json::Null, "world", true]
+      };
+
+      let mut slices = vec![
+        &input["list"]
+      ];
+
+      let mut output = vec![];
+      {
+        let mut gen = HighlightGenerator::new().with_output(&mut output);
+
+        gen.write_json_with_highlight(
+          &input, &mut slices
+        ).expect("Can't fail");
+
+        assert_eq!(gen.consume(), "");
+      }
+
+    assert_eq!(
+      String::from_utf8(output).unwrap(),
+      format!(
+        "{}{}{}",
+        r#"{"foo":false,"list":"#,
+        r#"[null,"world",true]"#.red(),
+        r#"}"#
+      )
+    );
+  }
+
+  #[test]
+  fn should_stream_large_matches_in_chunks() {
+      let input = JsonValue::Array((0..5000).map(|index| format!("item {}", index).into()).collect());
+
+      let mut slices = vec![
+        &input
+      ];
+
+      let mut output = vec![];
+      HighlightGenerator::new().with_output(&mut output).write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+      let output = String::from_utf8(output).unwrap();
+      let red = "x".red().to_string();
+      let (start, end) = red.split_at(red.find('x').unwrap());
+      assert!(output.matches(start).count() > 1);
+      assert_eq!(output.replace(start, "").replace(&end[1..], ""), input.dump());
+  }
+
+  #[test]
+  fn should_stream_tokens_without_writing_each_one() {
+      struct CountingWriter<'w>(&'w mut Vec<u8>, &'w mut usize);
+
+      impl<'w> io::Write for CountingWriter<'w> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+          *self.1 += 1;
+          self.0.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+          Ok(())
+        }
+      }
+
+      let input = JsonValue::Array((0..1000).map(|index| index.into()).collect());
+
+      let (mut output, mut writes) = (vec![], 0);
+      HighlightGenerator::new()
+        .with_theme(Theme::classic())
+        .with_output(CountingWriter(&mut output, &mut writes))
+        .write_json_with_highlight(&input, &mut vec![&input[500]])
+        .expect("Can't fail");
+
+      let mut gen = HighlightGenerator::new().with_theme(Theme::classic());
+      gen.write_json_with_highlight(&input, &mut vec![&input[500]]).expect("Can't fail");
+
+      assert_eq!(String::from_utf8(output).unwrap(), gen.consume());
+      assert!(writes < 10, "{} writes", writes);
+  }
+
+  #[test]
+  fn should_report_unmatched_slices() {
+      let input = object!{
+        "foo" => false,
+        "list" => array![json::Null, "world", true]
+      };
+      let copy = input.clone();
+
+      let mut slices = vec![
+        &input["missing"],
+        &input["list"],
+        &copy["foo"],
+        &input["list"]
+      ];
+
+      let mut gen = HighlightGenerator::new();
+
+      match gen.write_json_with_highlight_strict(&input, &mut slices) {
+        Err(Error::UnmatchedSlices(indices)) => assert_eq!(indices, vec![0, 2]),
+        _ => panic!("Expected unmatched slices")
+      }
+
+    assert_eq!(
+      gen.report(),
+      HighlightReport {
+        output: format!(
+          "{}{}{}",
+          r#"{"foo":false,"list":"#,
+          r#"[null,"world",true]"#.red(),
+          r#"}"#
+        ),
+        unmatched_slices: vec![0, 2]
+      }
+    );
+  }
+
+  #[test]
+  fn should_color_tokens_underneath_highlights() {
+      let input = object!{
+        "foo" => false,
+        "bar" => json::Null,
+        "list" => array![42, "world"]
+      };
+
+      let mut slices = vec![
+        &input["list"]
+      ];
+
+      let mut gen = HighlightGenerator::new().with_theme(Theme {
+        key: Some(Color::Blue.into()),
+        boolean: Some(Color::Yellow.into()),
+        punctuation: Some(Color::White.into()),
+        ..Theme::new()
+      });
+
+      gen.write_json_with_highlight(
+        &input, &mut slices
+      ).expect("Can't fail");
+
+    assert_eq!(
+      gen.consume(),
+      format!(
+        "{}{}{}{}{}{}{}{}{}{}{}{}{}",
+        "{".white(),
+        r#""foo""#.blue(),
+        ":".white(),
+        "false".yellow(),
+        ",".white(),
+        r#""bar""#.blue(),
+        ":".white(),
+        "null",
+        ",".white(),
+        r#""list""#.blue(),
+        ":".white(),
+        r#"[42,"world"]"#.red(),
+        "}".white()
+      )
+    );
+  }
+}
@@ -1,63 +1,222 @@
 use colored::*;
+use json::JsonValue;
 
-pub trait HighlightColor {
-  fn get_color(&mut self) -> Color;
+use crate::style::Style;
+use crate::path::Path;
+
+/// What is about to be highlighted: the node matching a slice, and where it is
+#[derive(Debug, Clone, Copy)]
+pub struct HighlightContext<'a, N = JsonValue> {
+  pub path: &'a Path,
+  pub depth: usize,
+  pub value: &'a N,
+  /// Index of the matched slice, in the order the slices were requested
+  pub slice_index: usize
+}
+
+/// Decides the style of every highlighted slice
+pub trait HighlightColor<N = JsonValue> {
+  fn get_style(&mut self, context: &HighlightContext<N>) -> Style;
+}
+
+impl<N, F> HighlightColor<N> for F where F: FnMut(&HighlightContext<N>) -> Style {
+  fn get_style(&mut self, context: &HighlightContext<N>) -> Style {
+    self(context)
+  }
 }
 
 pub struct SingleColor {
-  color: Color
+  style: Style
 }
 
 impl SingleColor {
   pub fn new() -> Self {
     SingleColor {
-      color: Color::Red
+      style: Color::Red.into()
     }
   }
 }
 
-impl HighlightColor for SingleColor {
-  fn get_color(&mut self) -> Color {
-    self.color
+impl Default for SingleColor {
+  fn default() -> Self {
+    SingleColor::new()
+  }
+}
+
+impl<N> HighlightColor<N> for SingleColor {
+  fn get_style(&mut self, _context: &HighlightContext<N>) -> Style {
+    self.style
   }
 }
 
 pub struct CycledColors {
-  colors: Box<Iterator<Item = Color>>
+  styles: Box<dyn Iterator<Item = Style>>
 }
 
 impl CycledColors {
-  pub fn new(colors: Vec<Color>) -> Self {
+  /// Cycles through the given styles, or styles every slice red when there are none
+  pub fn new(styles: Vec<Style>) -> Self {
+    let styles = if styles.is_empty() { vec![Color::Red.into()] } else { styles };
     CycledColors {
-      colors: Box::new(colors.into_iter().cycle())
+      styles: Box::new(styles.into_iter().cycle())
+    }
+  }
+}
+
+impl<N> HighlightColor<N> for CycledColors {
+  fn get_style(&mut self, _context: &HighlightContext<N>) -> Style {
+    self.styles.next().unwrap()
+  }
+}
+
+/// Styles every slice by its position in the request, wherever it appears in the document
+pub struct SliceStyles {
+  styles: Vec<Style>
+}
+
+impl SliceStyles {
+  pub fn new(styles: Vec<Style>) -> Self {
+    SliceStyles {
+      styles
+    }
+  }
+}
+
+impl<N> HighlightColor<N> for SliceStyles {
+  fn get_style(&mut self, context: &HighlightContext<N>) -> Style {
+    self.styles.get(context.slice_index)
+      .copied()
+      .unwrap_or_else(|| Color::Red.into())
+  }
+}
+
+type GroupKey<'a, N> = Box<dyn FnMut(&HighlightContext<N>) -> String + 'a>;
+
+/// Styles every slice by a hash of its path, or of a group key, so it keeps
+/// the same style across runs and across documents
+pub struct HashedColors<'a, N = JsonValue> {
+  styles: Vec<Style>,
+  group: Option<GroupKey<'a, N>>
+}
+
+impl<'a, N> HashedColors<'a, N> {
+  pub fn new() -> Self {
+    HashedColors::with_styles(vec![])
+  }
+
+  /// Hashes into the given styles, or into the default palette when there are none
+  pub fn with_styles(styles: Vec<Style>) -> Self {
+    let styles = if styles.is_empty() { default_palette() } else { styles };
+    HashedColors {
+      styles,
+      group: None
     }
   }
+
+  /// Hashes the key returned by `group` instead of the path of the node
+  pub fn by_group<F>(mut self, group: F) -> Self where F: FnMut(&HighlightContext<N>) -> String + 'a {
+    self.group = Some(Box::new(group));
+    self
+  }
+
+  pub fn style_for(&self, key: &str) -> Style {
+    self.styles[(fnv1a(key.as_bytes()) % self.styles.len() as u64) as usize]
+  }
 }
 
-impl HighlightColor for CycledColors {
-  fn get_color(&mut self) -> Color {
-    self.colors.next().unwrap().clone()
+impl<'a, N> Default for HashedColors<'a, N> {
+  fn default() -> Self {
+    HashedColors::new()
   }
 }
 
+impl<'a, N> HighlightColor<N> for HashedColors<'a, N> {
+  fn get_style(&mut self, context: &HighlightContext<N>) -> Style {
+    let key = match self.group {
+      Some(ref mut group) => group(context),
+      None => context.path.to_string()
+    };
+    self.style_for(&key)
+  }
+}
+
+fn default_palette() -> Vec<Style> {
+  vec![
+    Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan,
+    Color::BrightRed, Color::BrightGreen, Color::BrightYellow, Color::BrightBlue, Color::BrightMagenta, Color::BrightCyan
+  ].into_iter().map(Style::from).collect()
+}
+
+// FNV-1a is spelled out, rather than using the standard library's hasher,
+// as its output must not change between Rust versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+    (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+  })
+}
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use json::object;
+  use crate::path::PathSegment;
 
   #[test]
   fn cycledcolors_should_cycle_through_colors() {
+    let value = JsonValue::Null;
+    let path = Path::root();
+    let context = HighlightContext { path: &path, depth: 0, value: &value, slice_index: 0 };
     let mut cycle = CycledColors::new(
       vec![
-        Color::Red,
-        Color::Green,
-        Color::Yellow
+        Color::Red.into(),
+        Color::Green.into(),
+        Style::new().fg(Color::Yellow).bold()
       ]
     );
     
-    assert_eq!(cycle.get_color(), Color::Red);
-    assert_eq!(cycle.get_color(), Color::Green);
-    assert_eq!(cycle.get_color(), Color::Yellow);
-    assert_eq!(cycle.get_color(), Color::Red);
+    assert_eq!(cycle.get_style(&context), Color::Red.into());
+    assert_eq!(cycle.get_style(&context), Color::Green.into());
+    assert_eq!(cycle.get_style(&context), Style::new().fg(Color::Yellow).bold());
+    assert_eq!(cycle.get_style(&context), Color::Red.into());
+  }
+
+  #[test]
+  fn cycledcolors_should_fall_back_to_red() {
+    let value = JsonValue::Null;
+    let path = Path::root();
+    let context = HighlightContext { path: &path, depth: 0, value: &value, slice_index: 0 };
+    let mut cycle = CycledColors::new(vec![]);
+
+    assert_eq!(cycle.get_style(&context), Color::Red.into());
+    assert_eq!(cycle.get_style(&context), Color::Red.into());
+  }
+
+  #[test]
+  fn hashedcolors_should_keep_styles_across_documents() {
+    let first = object!{ "status" => "ok", "latency" => 12 };
+    let second = object!{ "latency" => 340, "retries" => 2, "status" => "failed" };
+    let mut path = Path::root();
+    path.push(PathSegment::Key("status".to_string()));
+    let mut colors = HashedColors::new();
+
+    let first_style = colors.get_style(&HighlightContext { path: &path, depth: 1, value: &first["status"], slice_index: 0 });
+    let second_style = colors.get_style(&HighlightContext { path: &path, depth: 1, value: &second["status"], slice_index: 2 });
+
+    assert_eq!(first_style, second_style);
+    assert_eq!(first_style, HashedColors::<JsonValue>::new().style_for("/status"));
+    assert_eq!(first_style, HashedColors::<JsonValue>::with_styles(vec![]).style_for("/status"));
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
   }
-}
\ No newline at end of file
+
+  #[test]
+  fn hashedcolors_should_hash_group_keys() {
+    let value = JsonValue::from("failed");
+    let path = Path::root();
+    let mut colors = HashedColors::new().by_group(|context: &HighlightContext| context.value.to_string());
+
+    assert_eq!(
+      colors.get_style(&HighlightContext { path: &path, depth: 0, value: &value, slice_index: 0 }),
+      HashedColors::<JsonValue>::new().style_for("failed")
+    );
+  }
+}
@@ -0,0 +1,170 @@
+use colored::Color;
+
+use crate::style::Style;
+
+pub fn escape(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for ch in text.chars() {
+    match ch {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&#39;"),
+      _ => escaped.push(ch)
+    }
+  }
+  escaped
+}
+
+pub fn css_class(color: Color) -> &'static str {
+  match color {
+    Color::Black => "jhw-black",
+    Color::Red => "jhw-red",
+    Color::Green => "jhw-green",
+    Color::Yellow => "jhw-yellow",
+    Color::Blue => "jhw-blue",
+    Color::Magenta => "jhw-magenta",
+    Color::Cyan => "jhw-cyan",
+    Color::White => "jhw-white",
+    Color::BrightBlack => "jhw-bright-black",
+    Color::BrightRed => "jhw-bright-red",
+    Color::BrightGreen => "jhw-bright-green",
+    Color::BrightYellow => "jhw-bright-yellow",
+    Color::BrightBlue => "jhw-bright-blue",
+    Color::BrightMagenta => "jhw-bright-magenta",
+    Color::BrightCyan => "jhw-bright-cyan",
+    Color::BrightWhite => "jhw-bright-white"
+  }
+}
+
+// The palette of the default VS Code terminal theme
+pub fn css_color(color: Color) -> &'static str {
+  match color {
+    Color::Black => "#000000",
+    Color::Red => "#cd3131",
+    Color::Green => "#0dbc79",
+    Color::Yellow => "#e5e510",
+    Color::Blue => "#2472c8",
+    Color::Magenta => "#bc3fbc",
+    Color::Cyan => "#11a8cd",
+    Color::White => "#e5e5e5",
+    Color::BrightBlack => "#666666",
+    Color::BrightRed => "#f14c4c",
+    Color::BrightGreen => "#23d18b",
+    Color::BrightYellow => "#f5f543",
+    Color::BrightBlue => "#3b8eea",
+    Color::BrightMagenta => "#d670d6",
+    Color::BrightCyan => "#29b8db",
+    Color::BrightWhite => "#e5e5e5"
+  }
+}
+
+const REVERSED_RULE: &str = ".jhw .jhw-reversed { color: #1e1e1e; background-color: #cccccc; }";
+
+// Reversed text swaps its foreground and background colors, falling back on
+// the page's colors when either isn't set
+fn style_rules(style: &Style) -> Vec<(String, String)> {
+  let (foreground, background) = if style.reversed {
+    (style.background, style.foreground)
+  } else {
+    (style.foreground, style.background)
+  };
+  let mut rules = vec![];
+  if style.reversed {
+    rules.push(("jhw-reversed".to_string(), REVERSED_RULE.to_string()));
+  }
+  if let Some(color) = foreground {
+    rules.push((css_class(color).to_string(), format!(".jhw .{} {{ color: {}; }}", css_class(color), css_color(color))));
+  }
+  if let Some(color) = background {
+    let class = css_class(color).replacen("jhw-", "jhw-on-", 1);
+    rules.push((class.clone(), format!(".jhw .{} {{ background-color: {}; }}", class, css_color(color))));
+  }
+  if style.bold {
+    rules.push(("jhw-bold".to_string(), ".jhw .jhw-bold { font-weight: bold; }".to_string()));
+  }
+  if style.italic {
+    rules.push(("jhw-italic".to_string(), ".jhw .jhw-italic { font-style: italic; }".to_string()));
+  }
+  if style.underline {
+    rules.push(("jhw-underline".to_string(), ".jhw .jhw-underline { text-decoration: underline; }".to_string()));
+  }
+  rules
+}
+
+pub fn css_classes(style: &Style) -> Vec<String> {
+  style_rules(style).into_iter().map(|(class, _)| class).collect()
+}
+
+/// Wraps escaped text in a span carrying the CSS classes of its style
+pub fn span(text: &str, style: &Style) -> String {
+  let classes = css_classes(style);
+  if classes.is_empty() {
+    return escape(text);
+  }
+  format!("<span class=\"{}\">{}</span>", classes.join(" "), escape(text))
+}
+
+/// A stylesheet defining the CSS classes used by each of the styles
+pub fn stylesheet(styles: &[Style]) -> String {
+  let mut rules = vec![
+    ".jhw { background: #1e1e1e; color: #cccccc; }".to_string()
+  ];
+  // The reversed rule comes first, so the colors of a reversed style override it
+  if styles.iter().any(|style| style.reversed) {
+    rules.push(REVERSED_RULE.to_string());
+  }
+  for style in styles {
+    for (_, rule) in style_rules(style) {
+      if !rules.contains(&rule) {
+        rules.push(rule);
+      }
+    }
+  }
+  rules.join("\n")
+}
+
+/// A standalone HTML page displaying the rendered JSON with an embedded stylesheet
+pub fn page(body: &str, styles: &[Style]) -> String {
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n{}\n</style>\n</head>\n<body>\n<pre class=\"jhw\">{}</pre>\n</body>\n</html>\n",
+    stylesheet(styles),
+    body
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_escape_markup() {
+    assert_eq!(
+      span(r#"{"html":"<b>Tom & Jerry's</b>"}"#, &Color::BrightBlue.into()),
+      r#"<span class="jhw-bright-blue">{&quot;html&quot;:&quot;&lt;b&gt;Tom &amp; Jerry&#39;s&lt;/b&gt;&quot;}</span>"#
+    );
+  }
+
+  #[test]
+  fn should_define_each_color_once_in_stylesheet() {
+    assert_eq!(
+      stylesheet(&[Color::Red.into(), Color::Green.into(), Color::Red.into()]),
+      ".jhw { background: #1e1e1e; color: #cccccc; }\n.jhw .jhw-red { color: #cd3131; }\n.jhw .jhw-green { color: #0dbc79; }"
+    );
+  }
+
+  #[test]
+  fn should_swap_colors_of_reversed_styles() {
+    let style = Style::new().fg(Color::Red).bold().reversed();
+
+    assert_eq!(
+      span("text", &style),
+      r#"<span class="jhw-reversed jhw-on-red jhw-bold">text</span>"#
+    );
+    assert_eq!(
+      stylesheet(&[style]),
+      ".jhw { background: #1e1e1e; color: #cccccc; }\n.jhw .jhw-reversed { color: #1e1e1e; background-color: #cccccc; }\n.jhw .jhw-on-red { background-color: #cd3131; }\n.jhw .jhw-bold { font-weight: bold; }"
+    );
+  }
+}
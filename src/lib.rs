@@ -1,30 +1,487 @@
+use std::io;
 use json::JsonValue;
 use colored::*;
 
 mod generator;
 mod highlight_color;
 mod highlight;
+mod pretty;
+mod error;
+mod theme;
+mod style;
+mod span;
+mod segment;
+mod tree;
+mod parse_error;
+pub mod pointer;
+pub mod selector;
+pub mod path;
+pub mod html;
+pub mod diff;
+pub mod source;
 
-pub fn highlight(json_object: &JsonValue, mut slices: Vec<&JsonValue>) -> String {
+pub use highlight::{HighlightGenerator, HighlightReport};
+pub use pretty::{PrettyConfig, Indent, LineEnding};
+pub use path::{Path, PathSegment};
+pub use error::{Error, Result};
+pub use theme::{Theme, Token};
+pub use style::Style;
+pub use span::{Span, SpanMap, Position};
+pub use segment::{Segment, Line};
+pub use tree::{JsonTree, Node, Items, Entries};
+pub use highlight_color::{HighlightColor, HighlightContext, SingleColor, CycledColors, SliceStyles, HashedColors};
+
+pub fn highlight<N: JsonTree>(json_object: &N, slices: Vec<&N>) -> String {
+    try_highlight(json_object, slices).expect("Can't fail")
+}
+
+pub fn try_highlight<N: JsonTree>(json_object: &N, mut slices: Vec<&N>) -> Result<String> {
     let mut gen = highlight::HighlightGenerator::new();
     gen.write_json_with_highlight(
       json_object, &mut slices
-    ).expect("Can't fail");
-    gen.consume()
+    )?;
+    Ok(gen.consume())
+}
+
+/// Fails with `Error::UnmatchedSlices` if any of the slices isn't found in the JSON object
+pub fn try_highlight_strict<N: JsonTree>(json_object: &N, mut slices: Vec<&N>) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_json_with_highlight_strict(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
 }
 
-pub fn highlight_with_colors(json_object: &JsonValue, mut slices: Vec<&JsonValue>, colors: Vec<Color>) -> String {
+pub fn highlight_with_report<N: JsonTree>(json_object: &N, slices: Vec<&N>) -> HighlightReport {
+    try_highlight_with_report(json_object, slices).expect("Can't fail")
+}
+
+pub fn try_highlight_with_report<N: JsonTree>(json_object: &N, mut slices: Vec<&N>) -> Result<HighlightReport> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.report())
+}
+
+pub fn highlight_with_colors<N: JsonTree>(json_object: &N, slices: Vec<&N>, colors: Vec<Color>) -> String {
+    try_highlight_with_colors(json_object, slices, colors).expect("Can't fail")
+}
+
+pub fn try_highlight_with_colors<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, colors: Vec<Color>) -> Result<String> {
     let mut gen = highlight::HighlightGenerator::new_with_colors(colors);
     gen.write_json_with_highlight(
       json_object, &mut slices
-    ).expect("Can't fail");
-    gen.consume()
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_with_colors_and_remainder<N: JsonTree>(json_object: &N, slices: Vec<&N>, colors: Option<Vec<Color>>, remainder_color: Option<Color>) -> String {
+    try_highlight_with_colors_and_remainder(json_object, slices, colors, remainder_color).expect("Can't fail")
 }
 
-pub fn highlight_with_colors_and_remainder(json_object: &JsonValue, mut slices: Vec<&JsonValue>, colors: Option<Vec<Color>>, remainder_color: Option<Color>) -> String {
+pub fn try_highlight_with_colors_and_remainder<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, colors: Option<Vec<Color>>, remainder_color: Option<Color>) -> Result<String> {
     let mut gen = highlight::HighlightGenerator::new_with_colors_and_remainder(colors, remainder_color);
     gen.write_json_with_highlight(
       json_object, &mut slices
-    ).expect("Can't fail");
-    gen.consume()
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_with_styles<N: JsonTree>(json_object: &N, slices: Vec<&N>, styles: Vec<Style>) -> String {
+    try_highlight_with_styles(json_object, slices, styles).expect("Can't fail")
+}
+
+pub fn try_highlight_with_styles<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, styles: Vec<Style>) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new_with_styles(styles);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_with_strategy<'a, N: JsonTree, C: HighlightColor<N> + 'a>(json_object: &N, slices: Vec<&'a N>, strategy: C) -> String {
+    try_highlight_with_strategy(json_object, slices, strategy).expect("Can't fail")
+}
+
+pub fn try_highlight_with_strategy<'a, N: JsonTree, C: HighlightColor<N> + 'a>(json_object: &N, mut slices: Vec<&'a N>, strategy: C) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_highlight_color(strategy);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Highlights every slice in its own style, regardless of where it appears in the JSON object
+pub fn highlight_with_slice_styles<N: JsonTree>(json_object: &N, slices: Vec<(&N, Style)>) -> String {
+    try_highlight_with_slice_styles(json_object, slices).expect("Can't fail")
+}
+
+pub fn try_highlight_with_slice_styles<N: JsonTree>(json_object: &N, slices: Vec<(&N, Style)>) -> Result<String> {
+    let (slices, styles) = slices.into_iter().unzip();
+    try_highlight_with_strategy(json_object, slices, SliceStyles::new(styles))
+}
+
+pub fn highlight_pretty<N: JsonTree>(json_object: &N, slices: Vec<&N>, pretty: PrettyConfig) -> String {
+    try_highlight_pretty(json_object, slices, pretty).expect("Can't fail")
+}
+
+pub fn try_highlight_pretty<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, pretty: PrettyConfig) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_pretty(pretty);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Collapses subtrees without any highlighted node, keeping `context` siblings around each match
+pub fn highlight_collapsed<N: JsonTree>(json_object: &N, slices: Vec<&N>, context: usize) -> String {
+    try_highlight_collapsed(json_object, slices, context).expect("Can't fail")
+}
+
+pub fn try_highlight_collapsed<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, context: usize) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_collapse(context);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Writes only the first and last `items` of longer arrays, along with every item containing a match
+pub fn highlight_sampled<N: JsonTree>(json_object: &N, slices: Vec<&N>, items: usize) -> String {
+    try_highlight_sampled(json_object, slices, items).expect("Can't fail")
+}
+
+pub fn try_highlight_sampled<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, items: usize) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_array_sampling(items);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Truncates string values longer than `chars` characters, keeping highlighted strings whole
+pub fn highlight_truncated<N: JsonTree>(json_object: &N, slices: Vec<&N>, chars: usize) -> String {
+    try_highlight_truncated(json_object, slices, chars).expect("Can't fail")
+}
+
+pub fn try_highlight_truncated<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, chars: usize) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_string_limit(chars);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Shows objects and arrays nested deeper than `depth` as `{…}` or `[…]`, except towards the highlighted slices
+pub fn highlight_with_max_depth<N: JsonTree>(json_object: &N, slices: Vec<&N>, depth: usize) -> String {
+    try_highlight_with_max_depth(json_object, slices, depth).expect("Can't fail")
+}
+
+pub fn try_highlight_with_max_depth<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, depth: usize) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_max_depth(depth);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_with_theme<N: JsonTree>(json_object: &N, slices: Vec<&N>, theme: Theme) -> String {
+    try_highlight_with_theme(json_object, slices, theme).expect("Can't fail")
+}
+
+pub fn try_highlight_with_theme<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, theme: Theme) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new().with_theme(theme);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_pointers(json_object: &JsonValue, pointers: Vec<&str>) -> String {
+    highlight(json_object, pointer::resolve_all(json_object, &pointers))
+}
+
+/// Fails if any of the pointers is malformed or doesn't address a node in the JSON object
+pub fn try_highlight_pointers(json_object: &JsonValue, pointers: Vec<&str>) -> Result<String> {
+    try_highlight(json_object, pointer::try_resolve_all(json_object, &pointers)?)
+}
+
+pub fn highlight_pointers_with_colors(json_object: &JsonValue, pointers: Vec<&str>, colors: Vec<Color>) -> String {
+    highlight_with_colors(json_object, pointer::resolve_all(json_object, &pointers), colors)
+}
+
+/// Fails if any of the pointers is malformed or doesn't address a node in the JSON object
+pub fn try_highlight_pointers_with_colors(json_object: &JsonValue, pointers: Vec<&str>, colors: Vec<Color>) -> Result<String> {
+    try_highlight_with_colors(json_object, pointer::try_resolve_all(json_object, &pointers)?, colors)
+}
+
+pub fn highlight_pointers_with_styles(json_object: &JsonValue, pointers: Vec<(&str, Style)>) -> String {
+    let slices = pointers.into_iter()
+        .filter_map(|(pointer, style)| pointer::resolve(json_object, pointer).map(|node| (node, style)))
+        .collect();
+    highlight_with_slice_styles(json_object, slices)
+}
+
+/// Fails if any of the pointers is malformed or doesn't address a node in the JSON object
+pub fn try_highlight_pointers_with_styles(json_object: &JsonValue, pointers: Vec<(&str, Style)>) -> Result<String> {
+    let (pointers, styles) : (Vec<&str>, Vec<Style>) = pointers.into_iter().unzip();
+    let slices = pointer::try_resolve_all(json_object, &pointers)?;
+    try_highlight_with_slice_styles(json_object, slices.into_iter().zip(styles).collect())
+}
+
+pub fn try_highlight_selectors(json_object: &JsonValue, selectors: Vec<&str>) -> Result<String> {
+    try_highlight(json_object, selector::select_all(json_object, &selectors)?)
+}
+
+pub fn try_highlight_selectors_with_colors(json_object: &JsonValue, selectors: Vec<&str>, colors: Vec<Color>) -> Result<String> {
+    try_highlight_with_colors(json_object, selector::select_all(json_object, &selectors)?, colors)
+}
+
+pub fn highlight_where<N, F, S>(json_object: &N, predicate: F) -> String
+    where N: JsonTree, F: FnMut(&Path, &N) -> Option<S>, S: Into<Style> {
+    try_highlight_where(json_object, predicate).expect("Can't fail")
+}
+
+pub fn try_highlight_where<N, F, S>(json_object: &N, mut predicate: F) -> Result<String>
+    where N: JsonTree, F: FnMut(&Path, &N) -> Option<S>, S: Into<Style> {
+    let mut slices = vec![];
+    path::walk(json_object, &mut |path, value| {
+        if let Some(style) = predicate(path, value) {
+            slices.push((value, style.into()));
+        }
+    });
+    try_highlight_with_slice_styles(json_object, slices)
+}
+
+pub fn highlight_html<N: JsonTree>(json_object: &N, slices: Vec<&N>) -> String {
+    try_highlight_html(json_object, slices).expect("Can't fail")
+}
+
+pub fn try_highlight_html<N: JsonTree>(json_object: &N, mut slices: Vec<&N>) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume_html())
+}
+
+/// Renders the plain JSON text, along with where each highlighted slice was written in it
+pub fn highlight_spans<N: JsonTree>(json_object: &N, slices: Vec<&N>) -> SpanMap {
+    try_highlight_spans(json_object, slices).expect("Can't fail")
+}
+
+pub fn try_highlight_spans<N: JsonTree>(json_object: &N, mut slices: Vec<&N>) -> Result<SpanMap> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume_spans())
+}
+
+/// Renders the highlighted JSON as lines of styled segments, rather than a string of ANSI codes
+pub fn highlight_lines<N: JsonTree>(json_object: &N, slices: Vec<&N>, pretty: PrettyConfig) -> Vec<Line> {
+    try_highlight_lines(json_object, slices, pretty).expect("Can't fail")
+}
+
+pub fn try_highlight_lines<N: JsonTree>(json_object: &N, mut slices: Vec<&N>, pretty: PrettyConfig) -> Result<Vec<Line>> {
+    let mut gen = highlight::HighlightGenerator::new().with_pretty(pretty);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(gen.consume_lines())
+}
+
+pub fn try_highlight_to_writer<'a, N: JsonTree, W: io::Write + 'a>(json_object: &N, mut slices: Vec<&'a N>, writer: W) -> Result<()> {
+    let mut gen = highlight::HighlightGenerator::new().with_output(writer);
+    gen.write_json_with_highlight(
+      json_object, &mut slices
+    )?;
+    Ok(())
+}
+
+/// Highlights the slices within the source text the JSON object was parsed from,
+/// leaving its formatting exactly as it was written
+pub fn try_highlight_source<N: JsonTree>(source: &str, json_object: &N, mut slices: Vec<&N>) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_source_with_highlight(
+      source, json_object, &mut slices
+    )?;
+    Ok(gen.consume())
+}
+
+/// Renders where `json::parse` failed on the source, highlighting the offending character in red
+pub fn highlight_parse_error(source: &str, error: &json::Error) -> String {
+    try_highlight_parse_error(source, error).expect("Can't fail")
+}
+
+pub fn try_highlight_parse_error(source: &str, error: &json::Error) -> Result<String> {
+    let mut gen : highlight::HighlightGenerator = highlight::HighlightGenerator::new();
+    gen.write_parse_error(
+      source, error, Color::Red.into()
+    )?;
+    Ok(gen.consume())
+}
+
+pub fn highlight_parse_error_html(source: &str, error: &json::Error) -> String {
+    try_highlight_parse_error_html(source, error).expect("Can't fail")
+}
+
+pub fn try_highlight_parse_error_html(source: &str, error: &json::Error) -> Result<String> {
+    let mut gen : highlight::HighlightGenerator = highlight::HighlightGenerator::new();
+    gen.write_parse_error(
+      source, error, Color::Red.into()
+    )?;
+    Ok(gen.consume_html())
+}
+
+pub fn highlight_diff(expected: &JsonValue, actual: &JsonValue) -> String {
+    try_highlight_diff(expected, actual).expect("Can't fail")
+}
+
+pub fn try_highlight_diff(expected: &JsonValue, actual: &JsonValue) -> Result<String> {
+    let mut gen = highlight::HighlightGenerator::new();
+    gen.write_diff(
+      &diff::diff(expected, actual), diff::DiffColors::default()
+    )?;
+    Ok(gen.consume())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json::{object, array};
+
+    #[test]
+    fn should_highlight_nodes_chosen_by_predicate() {
+        let input = object!{
+            "balance" => -5,
+            "history" => array![10, -2, "-1"]
+        };
+
+        let output = highlight_where(&input, |path, value| match value.as_f64() {
+            Some(number) if number < 0.0 && path.depth() > 1 => Some(Color::Yellow),
+            Some(number) if number < 0.0 => Some(Color::Red),
+            _ => None
+        });
+
+        assert_eq!(
+            output,
+            format!(
+                "{}{}{}{}{}",
+                r#"{"balance":"#,
+                "-5".red(),
+                r#","history":[10,"#,
+                "-2".yellow(),
+                r#","-1"]}"#
+            )
+        );
+    }
+
+    #[test]
+    fn should_keep_slice_styles_regardless_of_document_order() {
+        let input = object!{
+            "warnings" => array!["deprecated"],
+            "errors" => array!["timeout"]
+        };
+
+        let output = highlight_pointers_with_styles(&input, vec![
+            ("/errors", Color::Red.into()),
+            ("/missing", Color::Blue.into()),
+            ("/warnings", Style::new().fg(Color::Yellow).bold())
+        ]);
+
+        assert_eq!(
+            output,
+            format!(
+                "{}{}{}{}{}",
+                r#"{"warnings":"#,
+                r#"["deprecated"]"#.yellow().bold(),
+                r#","errors":"#,
+                r#"["timeout"]"#.red(),
+                "}"
+            )
+        );
+    }
+
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_return_errors_instead_of_panicking() {
+        let input = object!{
+            "code" => 200
+        };
+
+        match try_highlight_to_writer(&input, vec![&input["code"]], FailingWriter) {
+            Err(Error::Io(error)) => assert_eq!(error.kind(), io::ErrorKind::BrokenPipe),
+            _ => panic!("Expected an IO error")
+        }
+        match try_highlight_pointers(&input, vec!["/code", "/missing"]) {
+            Err(Error::UnmatchedSlices(indices)) => assert_eq!(indices, vec![1]),
+            _ => panic!("Expected unmatched slices")
+        }
+        match try_highlight_selectors(&input, vec!["$.code["]) {
+            Err(Error::InvalidSelector(error)) => assert_eq!(error.position, 7),
+            _ => panic!("Expected an invalid selector")
+        }
+    }
+
+    #[test]
+    fn should_fall_back_to_red_without_colors() {
+        let input = object!{
+            "code" => 200
+        };
+        let expected = format!("{}{}{}", r#"{"code":"#, "200".red(), "}");
+
+        assert_eq!(try_highlight_with_colors(&input, vec![&input["code"]], vec![]).unwrap(), expected);
+        assert_eq!(try_highlight_with_styles(&input, vec![&input["code"]], vec![]).unwrap(), expected);
+        assert_eq!(highlight_with_styles(&input, vec![&input["code"]], vec![]), expected);
+    }
+
+    #[test]
+    fn should_expose_every_try_entry_point() {
+        let source = r#"{"code": 200, "tags": ["a", "b"]}"#;
+        let input = json::parse(source).unwrap();
+        let code = || vec![&input["code"]];
+        let error = json::parse("[1,]").unwrap_err();
+
+        assert!(try_highlight(&input, code()).is_ok());
+        assert!(try_highlight_strict(&input, code()).is_ok());
+        assert!(try_highlight_with_report(&input, code()).is_ok());
+        assert!(try_highlight_with_colors(&input, code(), vec![Color::Green]).is_ok());
+        assert!(try_highlight_with_colors_and_remainder(&input, code(), None, Some(Color::Blue)).is_ok());
+        assert!(try_highlight_with_styles(&input, code(), vec![Color::Green.into()]).is_ok());
+        assert!(try_highlight_with_strategy(&input, code(), SingleColor::new()).is_ok());
+        assert!(try_highlight_with_slice_styles(&input, vec![(&input["code"], Color::Green.into())]).is_ok());
+        assert!(try_highlight_pretty(&input, code(), PrettyConfig::default()).is_ok());
+        assert!(try_highlight_collapsed(&input, code(), 1).is_ok());
+        assert!(try_highlight_sampled(&input, code(), 1).is_ok());
+        assert!(try_highlight_truncated(&input, code(), 1).is_ok());
+        assert!(try_highlight_with_max_depth(&input, code(), 1).is_ok());
+        assert!(try_highlight_with_theme(&input, code(), Theme::classic()).is_ok());
+        assert!(try_highlight_pointers(&input, vec!["/code"]).is_ok());
+        assert!(try_highlight_pointers_with_colors(&input, vec!["/code"], vec![Color::Green]).is_ok());
+        assert!(try_highlight_pointers_with_styles(&input, vec![("/code", Color::Green.into())]).is_ok());
+        assert!(try_highlight_selectors(&input, vec!["$.tags[0]"]).is_ok());
+        assert!(try_highlight_selectors_with_colors(&input, vec!["$.tags[0]"], vec![Color::Green]).is_ok());
+        assert!(try_highlight_where(&input, |_, value| value.as_str().map(|_| Color::Green)).is_ok());
+        assert!(try_highlight_html(&input, code()).is_ok());
+        assert!(try_highlight_spans(&input, code()).is_ok());
+        assert!(try_highlight_lines(&input, code(), PrettyConfig::default()).is_ok());
+        assert!(try_highlight_to_writer(&input, code(), Vec::new()).is_ok());
+        assert!(try_highlight_source(source, &input, code()).is_ok());
+        assert!(try_highlight_parse_error(source, &error).is_ok());
+        assert!(try_highlight_parse_error_html(source, &error).is_ok());
+        assert!(try_highlight_diff(&input, &object!{ "code" => 404 }).is_ok());
+    }
 }
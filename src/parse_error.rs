@@ -0,0 +1,163 @@
+use std::io;
+
+use crate::highlight::HighlightGenerator;
+use crate::generator::codegen::Generator;
+use crate::style::Style;
+use crate::tree::JsonTree;
+
+/// Lines of source shown before and after the line where parsing failed
+const CONTEXT_LINES: usize = 2;
+
+// Where parsing failed: the line and character column (both zero-based), the text
+// found there, and what went wrong
+struct Location<'s> {
+  line: usize,
+  column: usize,
+  found: &'s str,
+  label: &'static str
+}
+
+impl<'a, N: JsonTree> HighlightGenerator<'a, N> {
+  /// Writes the error a JSON source failed to parse with, followed by the lines of source
+  /// around where it failed, with the offending character highlighted and marked by a caret
+  pub fn write_parse_error(&mut self, source: &str, error: &json::Error, style: Style) -> io::Result<()> {
+    let lines : Vec<&str> = source.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line)).collect();
+    let location = match locate(&lines, error) {
+      Some(location) => location,
+      None => {
+        self.write(error.to_string().as_bytes())?;
+        return self.finish();
+      }
+    };
+    let message = match *error {
+      json::Error::UnexpectedCharacter { ch, .. } =>
+        format!("Unexpected character: {} at ({}:{})", ch, location.line + 1, location.column + 1),
+      _ => error.to_string()
+    };
+    self.write(message.as_bytes())?;
+
+    let first = location.line.saturating_sub(CONTEXT_LINES);
+    let last = (location.line + CONTEXT_LINES).min(lines.len() - 1);
+    let width = (last + 1).to_string().len();
+    for (index, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+      self.write(format!("\n{:>width$} |", index + 1, width = width).as_bytes())?;
+      if line.is_empty() && index != location.line {
+        continue;
+      }
+      self.write(b" ")?;
+      if index != location.line {
+        self.write(line.as_bytes())?;
+        continue;
+      }
+      let (before, after) = line.split_at(byte_offset(line, location.column));
+      self.write(before.as_bytes())?;
+      if !location.found.is_empty() {
+        self.write_highlighted(style, |gen| gen.write(location.found.as_bytes()))?;
+      }
+      self.write(&after.as_bytes()[location.found.len()..])?;
+
+      // Tabs are kept, so the caret lines up however wide they are shown
+      let padding : String = before.chars().map(|ch| if ch == '\t' { '\t' } else { ' ' }).collect();
+      self.write(format!("\n{:width$} | {}", "", padding, width = width).as_bytes())?;
+      self.write_highlighted(style, |gen| gen.write(format!("^ {}", location.label).as_bytes()))?;
+    }
+    self.finish()
+  }
+}
+
+fn locate<'s>(lines: &[&'s str], error: &json::Error) -> Option<Location<'s>> {
+  match *error {
+    json::Error::UnexpectedCharacter { ch, line, column } => {
+      let (mut line, mut column) = (line.checked_sub(1)?.min(lines.len() - 1), column.saturating_sub(1));
+      // json-rust places a character which starts a line at the end of the line before it
+      let starts_next_line = lines.get(line + 1).is_some_and(|next| next.starts_with(ch));
+      if column >= lines[line].chars().count() && starts_next_line {
+        line += 1;
+        column = 0;
+      }
+      let text = lines[line];
+      let offset = byte_offset(text, column);
+      let found = text[offset..].chars().next().map_or("", |found| &text[offset..offset + found.len_utf8()]);
+      Some(Location { line, column: text[..offset].chars().count(), found, label: "unexpected character" })
+    },
+    json::Error::UnexpectedEndOfJson => {
+      let line = lines.iter().rposition(|line| !line.trim().is_empty()).unwrap_or(0);
+      Some(Location { line, column: lines[line].trim_end().chars().count(), found: "", label: "unexpected end of JSON" })
+    },
+    _ => None
+  }
+}
+
+// The byte offset of a character column, or the end of the line when it is beyond it
+fn byte_offset(line: &str, column: usize) -> usize {
+  line.char_indices().nth(column).map_or(line.len(), |(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use colored::*;
+
+  fn render(source: &str) -> String {
+    let error = json::parse(source).unwrap_err();
+    let mut gen : HighlightGenerator = HighlightGenerator::new();
+    gen.write_parse_error(source, &error, Color::Red.into()).expect("Can't fail");
+    gen.consume()
+  }
+
+  #[test]
+  fn should_highlight_unexpected_characters() {
+    assert_eq!(
+      render("{\n  \"a\": 1,\n  \"é\": [1, }\n}"),
+      format!(
+        "{}{}{}{}{}",
+        "Unexpected character: } at (3:12)\n1 | {\n2 |   \"a\": 1,\n3 |   \"é\": [1, ",
+        "}".red(),
+        "\n  |            ",
+        "^ unexpected character".red(),
+        "\n4 | }"
+      )
+    );
+    assert_eq!(
+      render("[\r\n\t1,\r\n]"),
+      format!(
+        "{}{}{}{}",
+        "Unexpected character: ] at (3:1)\n1 | [\n2 | \t1,\n3 | ",
+        "]".red(),
+        "\n  | ",
+        "^ unexpected character".red()
+      )
+    );
+  }
+
+  #[test]
+  fn should_point_past_the_end_of_truncated_sources() {
+    assert_eq!(
+      render("{\"a\": [\n\t1,\n\n"),
+      format!(
+        "{}{}{}",
+        "Unexpected end of JSON\n1 | {\"a\": [\n2 | \t1,\n  | \t  ",
+        "^ unexpected end of JSON".red(),
+        "\n3 |\n4 |"
+      )
+    );
+  }
+
+  #[test]
+  fn should_render_as_html() {
+    let error = json::parse("[\"<b>\" x]").unwrap_err();
+
+    assert_eq!(
+      crate::highlight_parse_error_html("[\"<b>\" x]", &error),
+      "Unexpected character: x at (1:8)\n1 | [&quot;&lt;b&gt;&quot; <span class=\"jhw-red\">x</span>]\n  |        <span class=\"jhw-red\">^ unexpected character</span>"
+    );
+  }
+
+  #[test]
+  fn should_render_errors_without_a_location_as_is() {
+    let mut gen : HighlightGenerator = HighlightGenerator::new();
+    gen.write_parse_error("[]", &json::Error::ExceededDepthLimit, Color::Red.into()).expect("Can't fail");
+
+    assert_eq!(gen.consume(), "Exceeded depth limit");
+  }
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use crate::tree::{JsonTree, Node};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+  Key(String),
+  Index(usize)
+}
+
+/// Location of a node within a JSON object, displayed as a JSON Pointer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path {
+  segments: Vec<PathSegment>
+}
+
+impl Path {
+  pub fn root() -> Self {
+    Path {
+      segments: vec![]
+    }
+  }
+
+  pub fn segments(&self) -> &[PathSegment] {
+    &self.segments
+  }
+
+  pub fn depth(&self) -> usize {
+    self.segments.len()
+  }
+
+  pub fn push(&mut self, segment: PathSegment) {
+    self.segments.push(segment);
+  }
+
+  pub fn pop(&mut self) -> Option<PathSegment> {
+    self.segments.pop()
+  }
+}
+
+impl fmt::Display for Path {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for segment in &self.segments {
+      match segment {
+        PathSegment::Key(key) => write!(f, "/{}", key.replace('~', "~0").replace('/', "~1"))?,
+        PathSegment::Index(index) => write!(f, "/{}", index)?
+      }
+    }
+    Ok(())
+  }
+}
+
+/// Calls the visitor with every node in the JSON object, parents before their children
+pub fn walk<'a, N, F>(json: &'a N, visitor: &mut F) where N: JsonTree, F: FnMut(&Path, &'a N) {
+  walk_from(json, &mut Path::root(), visitor);
+}
+
+fn walk_from<'a, N, F>(json: &'a N, path: &mut Path, visitor: &mut F) where N: JsonTree, F: FnMut(&Path, &'a N) {
+  visitor(path, json);
+  match json.node() {
+    Node::Object => {
+      for (key, value) in json.entries() {
+        path.push(PathSegment::Key(key.to_string()));
+        walk_from(value, path, visitor);
+        path.pop();
+      }
+    },
+    Node::Array => {
+      for (index, item) in json.items().enumerate() {
+        path.push(PathSegment::Index(index));
+        walk_from(item, path, visitor);
+        path.pop();
+      }
+    },
+    _ => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use json::*;
+
+  #[test]
+  fn should_walk_parents_before_children() {
+    let input = object!{
+      "a/b" => array![1, object!{ "m~n" => true }]
+    };
+
+    let mut paths = vec![];
+    walk(&input, &mut |path, _| paths.push(path.to_string()));
+
+    assert_eq!(paths, vec!["", "/a~1b", "/a~1b/0", "/a~1b/1", "/a~1b/1/m~0n"]);
+  }
+}
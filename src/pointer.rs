@@ -0,0 +1,147 @@
+use json::JsonValue;
+
+use crate::error::{Error, Result};
+
+/// Resolves an RFC 6901 JSON Pointer, such as `/payload/features/1`, against a JSON object.
+/// Returns `None` when the pointer is malformed or doesn't address a node in the object.
+pub fn resolve<'a>(json: &'a JsonValue, pointer: &str) -> Option<&'a JsonValue> {
+  try_resolve(json, pointer).ok().flatten()
+}
+
+/// Resolves a JSON Pointer, failing when it is malformed
+pub fn try_resolve<'a>(json: &'a JsonValue, pointer: &str) -> Result<Option<&'a JsonValue>> {
+  Ok(parse(pointer)?.iter().try_fold(json, |node, token| match *node {
+    JsonValue::Object(ref object) => object.get(token),
+    JsonValue::Array(ref array) => array_index(token).and_then(|index| array.get(index)),
+    _ => None
+  }))
+}
+
+/// Resolves every pointer, skipping those that don't address a node in the object
+pub fn resolve_all<'a>(json: &'a JsonValue, pointers: &[&str]) -> Vec<&'a JsonValue> {
+  pointers.iter()
+    .filter_map(|pointer| resolve(json, pointer))
+    .collect()
+}
+
+/// Resolves every pointer, failing when any of them is malformed or doesn't address a node in the object
+pub fn try_resolve_all<'a>(json: &'a JsonValue, pointers: &[&str]) -> Result<Vec<&'a JsonValue>> {
+  let mut nodes = vec![];
+  let mut unmatched = vec![];
+  for (index, pointer) in pointers.iter().enumerate() {
+    match try_resolve(json, pointer)? {
+      Some(node) => nodes.push(node),
+      None => unmatched.push(index)
+    }
+  }
+  if unmatched.is_empty() {
+    Ok(nodes)
+  } else {
+    Err(Error::UnmatchedSlices(unmatched))
+  }
+}
+
+/// Splits a JSON Pointer into its unescaped reference tokens
+pub fn parse(pointer: &str) -> Result<Vec<String>> {
+  if pointer.is_empty() {
+    return Ok(vec![]);
+  }
+  if !pointer.starts_with('/') {
+    return Err(Error::InvalidPointer(pointer.to_string()));
+  }
+  pointer[1..].split('/')
+    .map(|token| unescape(token).ok_or_else(|| Error::InvalidPointer(pointer.to_string())))
+    .collect()
+}
+
+fn unescape(token: &str) -> Option<String> {
+  let mut unescaped = String::with_capacity(token.len());
+  let mut chars = token.chars();
+  while let Some(ch) = chars.next() {
+    if ch == '~' {
+      match chars.next() {
+        Some('0') => unescaped.push('~'),
+        Some('1') => unescaped.push('/'),
+        _ => return None
+      }
+    } else {
+      unescaped.push(ch);
+    }
+  }
+  Some(unescaped)
+}
+
+fn array_index(token: &str) -> Option<usize> {
+  let is_canonical = !token.is_empty()
+    && token.bytes().all(|ch| ch.is_ascii_digit())
+    && (token == "0" || !token.starts_with('0'));
+  if is_canonical {
+    token.parse().ok()
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use json::*;
+  use std::ptr;
+  use crate::error::Error;
+
+  #[test]
+  fn should_resolve_nested_pointers() {
+    let input = object!{
+      "payload" => object!{
+        "features" => array!["awesome", "easyAPI"]
+      }
+    };
+
+    assert!(ptr::eq(resolve(&input, "").unwrap(), &input));
+    assert!(ptr::eq(resolve(&input, "/payload").unwrap(), &input["payload"]));
+    assert!(ptr::eq(resolve(&input, "/payload/features/1").unwrap(), &input["payload"]["features"][1]));
+  }
+
+  #[test]
+  fn should_unescape_reference_tokens() {
+    let input = object!{
+      "a/b" => 1,
+      "m~n" => 2
+    };
+
+    assert_eq!(resolve(&input, "/a~1b"), Some(&input["a/b"]));
+    assert_eq!(resolve(&input, "/m~0n"), Some(&input["m~n"]));
+    assert_eq!(resolve(&input, "/m~2n"), None);
+  }
+
+  #[test]
+  fn should_not_resolve_missing_or_malformed_pointers() {
+    let input = object!{
+      "list" => array![1, 2]
+    };
+
+    assert_eq!(resolve(&input, "list"), None);
+    assert_eq!(resolve(&input, "/missing"), None);
+    assert_eq!(resolve(&input, "/list/2"), None);
+    assert_eq!(resolve(&input, "/list/01"), None);
+    assert_eq!(resolve(&input, "/list/-"), None);
+    assert_eq!(resolve(&input, "/list/0/deeper"), None);
+  }
+
+  #[test]
+  fn should_fail_to_resolve_malformed_or_missing_pointers() {
+    let input = object!{
+      "list" => array![1, 2]
+    };
+
+    assert_eq!(try_resolve_all(&input, &["/list/1", "/list"]).unwrap(), vec![&input["list"][1], &input["list"]]);
+    match try_resolve_all(&input, &["/list/1", "/missing", "/list/7"]) {
+      Err(Error::UnmatchedSlices(indices)) => assert_eq!(indices, vec![1, 2]),
+      _ => panic!("Expected unmatched slices")
+    }
+    match try_resolve_all(&input, &["/list", "list"]) {
+      Err(Error::InvalidPointer(pointer)) => assert_eq!(pointer, "list"),
+      _ => panic!("Expected an invalid pointer")
+    }
+  }
+}
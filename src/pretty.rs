@@ -0,0 +1,57 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indent {
+  Spaces(u16),
+  Tabs
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineEnding {
+  Lf,
+  CrLf
+}
+
+/// Layout used when printing a JSON object across multiple lines
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrettyConfig {
+  pub indent: Indent,
+  pub line_ending: LineEnding,
+  pub space_after_colon: bool
+}
+
+impl PrettyConfig {
+  pub fn new(indent: Indent) -> Self {
+    PrettyConfig {
+      indent,
+      line_ending: LineEnding::Lf,
+      space_after_colon: true
+    }
+  }
+
+  pub fn with_spaces(spaces: u16) -> Self {
+    PrettyConfig::new(Indent::Spaces(spaces))
+  }
+
+  pub fn with_tabs() -> Self {
+    PrettyConfig::new(Indent::Tabs)
+  }
+
+  pub fn line_ending(&self) -> &'static [u8] {
+    match self.line_ending {
+      LineEnding::Lf => b"\n",
+      LineEnding::CrLf => b"\r\n"
+    }
+  }
+
+  pub fn indentation(&self, depth: u16) -> Vec<u8> {
+    match self.indent {
+      Indent::Spaces(spaces) => vec![b' '; spaces as usize * depth as usize],
+      Indent::Tabs => vec![b'\t'; depth as usize]
+    }
+  }
+}
+
+impl Default for PrettyConfig {
+  fn default() -> Self {
+    PrettyConfig::with_spaces(2)
+  }
+}
@@ -0,0 +1,67 @@
+use crate::style::Style;
+
+/// A piece of rendered text, written in a single style
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+  pub text: String,
+  pub style: Style
+}
+
+/// A line of rendered text, without its line ending
+pub type Line = Vec<Segment>;
+
+/// Splits styled text into lines, merging neighbouring pieces which share a style.
+/// Unstyled text gets the default, plain style.
+pub(crate) fn lines<'a, I>(slices: I) -> Vec<Line> where I: IntoIterator<Item = (&'a str, Option<Style>)> {
+  let mut lines = vec![vec![]];
+  for (text, style) in slices {
+    let style = style.unwrap_or_default();
+    for (index, part) in text.split('\n').enumerate() {
+      if index > 0 {
+        strip_carriage_return(lines.last_mut().unwrap());
+        lines.push(vec![]);
+      }
+      if part.is_empty() {
+        continue;
+      }
+      let line = lines.last_mut().unwrap();
+      match line.last_mut() {
+        Some(segment) if segment.style == style => segment.text.push_str(part),
+        _ => line.push(Segment { text: part.to_string(), style })
+      }
+    }
+  }
+  lines
+}
+
+fn strip_carriage_return(line: &mut Line) {
+  if let Some(segment) = line.last_mut() {
+    if segment.text.ends_with('\r') {
+      segment.text.pop();
+    }
+    if segment.text.is_empty() {
+      line.pop();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use colored::Color;
+
+  #[test]
+  fn should_split_styled_text_into_lines() {
+    let red = Style::from(Color::Red);
+
+    assert_eq!(
+      lines(vec![("{\r\n  \"a\": ", None), ("[\r\n", Some(red)), ("    1", Some(red)), ("\r\n", Some(red)), ("]", None)]),
+      vec![
+        vec![Segment { text: "{".to_string(), style: Style::default() }],
+        vec![Segment { text: "  \"a\": ".to_string(), style: Style::default() }, Segment { text: "[".to_string(), style: red }],
+        vec![Segment { text: "    1".to_string(), style: red }],
+        vec![Segment { text: "]".to_string(), style: Style::default() }]
+      ]
+    );
+  }
+}
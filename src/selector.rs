@@ -0,0 +1,666 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use json::JsonValue;
+
+/// Error describing why a JSONPath expression couldn't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+  pub position: usize,
+  pub message: String
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Invalid JSONPath at position {}: {}", self.position, self.message)
+  }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+  Child(Vec<Selection>),
+  Descendant(Vec<Selection>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selection {
+  Name(String),
+  Index(i64),
+  Wildcard,
+  Slice(Option<i64>, Option<i64>, i64),
+  Filter(Filter)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+  Or(Box<Filter>, Box<Filter>),
+  And(Box<Filter>, Box<Filter>),
+  Not(Box<Filter>),
+  Exists(Operand),
+  Compare(Operand, Comparison, Operand)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+  Current(Vec<Key>),
+  Root(Vec<Key>),
+  Literal(JsonValue)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Key {
+  Name(String),
+  Index(i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge
+}
+
+/// A parsed JSONPath expression, such as `$.items[*].price`, `$..id` or `$.jobs[?(@.status == 'failed')]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+  segments: Vec<Segment>
+}
+
+impl JsonPath {
+  pub fn parse(expression: &str) -> Result<JsonPath, ParseError> {
+    Parser::new(expression).parse_path()
+  }
+
+  /// Returns every node matched by the expression, each node at most once
+  pub fn select<'a>(&self, json: &'a JsonValue) -> Vec<&'a JsonValue> {
+    let nodes = self.segments.iter().fold(vec![json], |nodes, segment| {
+      let mut selected = vec![];
+      for node in nodes {
+        match segment {
+          Segment::Child(selections) => apply_selections(selections, node, json, &mut selected),
+          Segment::Descendant(selections) => {
+            let mut descendants = vec![];
+            collect_descendants(node, &mut descendants);
+            for descendant in descendants {
+              apply_selections(selections, descendant, json, &mut selected);
+            }
+          }
+        }
+      }
+      selected
+    });
+
+    let mut seen = HashSet::new();
+    nodes.into_iter()
+      .filter(|node| seen.insert(*node as *const JsonValue))
+      .collect()
+  }
+}
+
+/// Parses the expression and returns every node it matches in the JSON object
+pub fn select<'a>(json: &'a JsonValue, expression: &str) -> Result<Vec<&'a JsonValue>, ParseError> {
+  Ok(JsonPath::parse(expression)?.select(json))
+}
+
+/// Parses every expression and returns the nodes matched by any of them
+pub fn select_all<'a>(json: &'a JsonValue, expressions: &[&str]) -> Result<Vec<&'a JsonValue>, ParseError> {
+  let mut nodes = vec![];
+  for expression in expressions {
+    nodes.append(&mut select(json, expression)?);
+  }
+  Ok(nodes)
+}
+
+fn children(node: &JsonValue) -> Vec<&JsonValue> {
+  match *node {
+    JsonValue::Object(ref object) => object.iter().map(|(_, value)| value).collect(),
+    JsonValue::Array(ref array) => array.iter().collect(),
+    _ => vec![]
+  }
+}
+
+fn collect_descendants<'a>(node: &'a JsonValue, descendants: &mut Vec<&'a JsonValue>) {
+  descendants.push(node);
+  for child in children(node) {
+    collect_descendants(child, descendants);
+  }
+}
+
+fn apply_selections<'a>(selections: &[Selection], node: &'a JsonValue, root: &'a JsonValue, selected: &mut Vec<&'a JsonValue>) {
+  for selection in selections {
+    match selection {
+      Selection::Name(name) => {
+        if let JsonValue::Object(ref object) = *node {
+          selected.extend(object.get(name));
+        }
+      },
+      Selection::Index(index) => {
+        if let JsonValue::Array(ref array) = *node {
+          selected.extend(normalize_index(*index, array.len()).and_then(|index| array.get(index)));
+        }
+      },
+      Selection::Wildcard => selected.append(&mut children(node)),
+      Selection::Slice(start, end, step) => {
+        if let JsonValue::Array(ref array) = *node {
+          selected.extend(slice_indices(*start, *end, *step, array.len()).into_iter().map(|index| &array[index]));
+        }
+      },
+      Selection::Filter(filter) => {
+        selected.extend(children(node).into_iter().filter(|child| filter.test(child, root)));
+      }
+    }
+  }
+}
+
+fn normalize_index(index: i64, len: usize) -> Option<usize> {
+  let index = if index < 0 { len as i64 + index } else { index };
+  if index >= 0 && (index as usize) < len {
+    Some(index as usize)
+  } else {
+    None
+  }
+}
+
+fn slice_indices(start: Option<i64>, end: Option<i64>, step: i64, len: usize) -> Vec<usize> {
+  let len = len as i64;
+  let normalize = |index: i64| if index >= 0 { index } else { len + index };
+  let mut indices = vec![];
+  if step > 0 {
+    let lower = normalize(start.unwrap_or(0)).max(0).min(len);
+    let upper = normalize(end.unwrap_or(len)).max(0).min(len);
+    let mut index = Some(lower);
+    while let Some(current) = index.filter(|index| *index < upper) {
+      indices.push(current as usize);
+      index = current.checked_add(step);
+    }
+  } else if step < 0 {
+    let upper = start.map(normalize).unwrap_or(len - 1).max(-1).min(len - 1);
+    let lower = end.map(normalize).unwrap_or(-1).max(-1).min(len - 1);
+    let mut index = Some(upper);
+    while let Some(current) = index.filter(|index| lower < *index) {
+      indices.push(current as usize);
+      index = current.checked_add(step);
+    }
+  }
+  indices
+}
+
+impl Filter {
+  fn test(&self, current: &JsonValue, root: &JsonValue) -> bool {
+    match self {
+      Filter::Or(left, right) => left.test(current, root) || right.test(current, root),
+      Filter::And(left, right) => left.test(current, root) && right.test(current, root),
+      Filter::Not(filter) => !filter.test(current, root),
+      Filter::Exists(operand) => operand.resolve(current, root).is_some(),
+      Filter::Compare(left, comparison, right) => {
+        let ordering = match (left.resolve(current, root), right.resolve(current, root)) {
+          (None, None) => Some(Ordering::Equal),
+          (Some(left), Some(right)) => compare(left, right),
+          _ => None
+        };
+        match (comparison, ordering) {
+          (Comparison::Eq, Some(Ordering::Equal)) => true,
+          (Comparison::Ne, ordering) => ordering != Some(Ordering::Equal),
+          (Comparison::Lt, Some(Ordering::Less)) => true,
+          (Comparison::Le, Some(Ordering::Less)) | (Comparison::Le, Some(Ordering::Equal)) => true,
+          (Comparison::Gt, Some(Ordering::Greater)) => true,
+          (Comparison::Ge, Some(Ordering::Greater)) | (Comparison::Ge, Some(Ordering::Equal)) => true,
+          _ => false
+        }
+      }
+    }
+  }
+}
+
+// Numbers and strings are ordered, any other values can only be equal or not
+fn compare(left: &JsonValue, right: &JsonValue) -> Option<Ordering> {
+  if let (Some(left), Some(right)) = (left.as_str(), right.as_str()) {
+    return Some(left.cmp(right));
+  }
+  if left.is_number() && right.is_number() {
+    return left.as_f64().partial_cmp(&right.as_f64());
+  }
+  if left == right {
+    Some(Ordering::Equal)
+  } else {
+    None
+  }
+}
+
+impl Operand {
+  fn resolve<'a>(&'a self, current: &'a JsonValue, root: &'a JsonValue) -> Option<&'a JsonValue> {
+    let (node, keys) = match self {
+      Operand::Literal(value) => return Some(value),
+      Operand::Current(keys) => (current, keys),
+      Operand::Root(keys) => (root, keys)
+    };
+    keys.iter().try_fold(node, |node, key| match (key, node) {
+      (Key::Name(name), JsonValue::Object(ref object)) => object.get(name),
+      (Key::Index(index), JsonValue::Array(ref array)) => {
+        normalize_index(*index, array.len()).and_then(|index| array.get(index))
+      },
+      _ => None
+    })
+  }
+}
+
+struct Parser {
+  chars: Vec<char>,
+  position: usize
+}
+
+impl Parser {
+  fn new(expression: &str) -> Self {
+    Parser {
+      chars: expression.chars().collect(),
+      position: 0
+    }
+  }
+
+  fn error<T>(&self, message: &str) -> Result<T, ParseError> {
+    Err(ParseError {
+      position: self.position,
+      message: message.to_string()
+    })
+  }
+
+  fn peek(&self) -> Option<char> {
+    self.chars.get(self.position).cloned()
+  }
+
+  fn peek_str(&self, expected: &str) -> bool {
+    expected.chars().enumerate().all(|(offset, ch)| self.chars.get(self.position + offset) == Some(&ch))
+  }
+
+  fn eat(&mut self, expected: char) -> bool {
+    if self.peek() == Some(expected) {
+      self.position += 1;
+      true
+    } else {
+      false
+    }
+  }
+
+  fn eat_str(&mut self, expected: &str) -> bool {
+    if self.peek_str(expected) {
+      self.position += expected.chars().count();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+    if self.eat(expected) {
+      Ok(())
+    } else {
+      self.error(&format!("Expected '{}'", expected))
+    }
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.peek().is_some_and(char::is_whitespace) {
+      self.position += 1;
+    }
+  }
+
+  fn parse_path(&mut self) -> Result<JsonPath, ParseError> {
+    self.skip_whitespace();
+    self.expect('$')?;
+    let mut segments = vec![];
+    loop {
+      match self.peek() {
+        Some('.') if self.peek_str("..") => {
+          self.position += 2;
+          let selections = match self.peek() {
+            Some('[') => self.parse_bracket()?,
+            _ => vec![self.parse_dot_selection()?]
+          };
+          segments.push(Segment::Descendant(selections));
+        },
+        Some('.') => {
+          self.position += 1;
+          segments.push(Segment::Child(vec![self.parse_dot_selection()?]));
+        },
+        Some('[') => segments.push(Segment::Child(self.parse_bracket()?)),
+        Some(ch) if ch.is_whitespace() => {
+          self.skip_whitespace();
+          return match self.peek() {
+            None => Ok(JsonPath { segments }),
+            Some(_) => self.error("Unexpected character after expression")
+          };
+        },
+        Some(_) => return self.error("Expected '.', '..' or '['"),
+        None => return Ok(JsonPath { segments })
+      }
+    }
+  }
+
+  fn parse_dot_selection(&mut self) -> Result<Selection, ParseError> {
+    if self.eat('*') {
+      Ok(Selection::Wildcard)
+    } else {
+      Ok(Selection::Name(self.parse_name()?))
+    }
+  }
+
+  fn parse_name(&mut self) -> Result<String, ParseError> {
+    let start = self.position;
+    while self.peek().is_some_and(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-') {
+      self.position += 1;
+    }
+    if start == self.position {
+      return self.error("Expected a member name");
+    }
+    Ok(self.chars[start..self.position].iter().collect())
+  }
+
+  fn parse_bracket(&mut self) -> Result<Vec<Selection>, ParseError> {
+    self.expect('[')?;
+    self.skip_whitespace();
+    if self.eat('?') {
+      self.skip_whitespace();
+      self.expect('(')?;
+      let filter = self.parse_or()?;
+      self.skip_whitespace();
+      self.expect(')')?;
+      self.skip_whitespace();
+      self.expect(']')?;
+      return Ok(vec![Selection::Filter(filter)]);
+    }
+
+    let mut selections = vec![];
+    loop {
+      self.skip_whitespace();
+      selections.push(match self.peek() {
+        Some('*') => {
+          self.position += 1;
+          Selection::Wildcard
+        },
+        Some('\'') | Some('"') => Selection::Name(self.parse_string()?),
+        _ => self.parse_index_or_slice()?
+      });
+      self.skip_whitespace();
+      if self.eat(']') {
+        return Ok(selections);
+      }
+      if !self.eat(',') {
+        return self.error("Expected ',' or ']'");
+      }
+    }
+  }
+
+  fn parse_index_or_slice(&mut self) -> Result<Selection, ParseError> {
+    let start = self.parse_optional_integer()?;
+    self.skip_whitespace();
+    if !self.eat(':') {
+      return match start {
+        Some(index) => Ok(Selection::Index(index)),
+        None => self.error("Expected an index, a slice, a quoted name or '*'")
+      };
+    }
+    self.skip_whitespace();
+    let end = self.parse_optional_integer()?;
+    self.skip_whitespace();
+    let step = if self.eat(':') {
+      self.skip_whitespace();
+      self.parse_optional_integer()?.unwrap_or(1)
+    } else {
+      1
+    };
+    Ok(Selection::Slice(start, end, step))
+  }
+
+  fn parse_optional_integer(&mut self) -> Result<Option<i64>, ParseError> {
+    let start = self.position;
+    self.eat('-');
+    while self.peek().is_some_and(|ch| ch.is_ascii_digit()) {
+      self.position += 1;
+    }
+    if start == self.position {
+      return Ok(None);
+    }
+    let digits: String = self.chars[start..self.position].iter().collect();
+    match digits.parse() {
+      Ok(integer) => Ok(Some(integer)),
+      Err(_) => {
+        self.position = start;
+        self.error("Invalid integer")
+      }
+    }
+  }
+
+  fn parse_string(&mut self) -> Result<String, ParseError> {
+    let quote = match self.peek() {
+      Some(quote) => quote,
+      None => return self.error("Expected a quoted string")
+    };
+    self.position += 1;
+    let mut string = String::new();
+    loop {
+      match self.peek() {
+        Some('\\') => {
+          self.position += 1;
+          match self.peek() {
+            Some(ch) => string.push(ch),
+            None => return self.error("Unterminated string")
+          }
+        },
+        Some(ch) if ch == quote => {
+          self.position += 1;
+          return Ok(string);
+        },
+        Some(ch) => string.push(ch),
+        None => return self.error("Unterminated string")
+      }
+      self.position += 1;
+    }
+  }
+
+  fn parse_or(&mut self) -> Result<Filter, ParseError> {
+    let mut filter = self.parse_and()?;
+    loop {
+      self.skip_whitespace();
+      if !self.eat_str("||") {
+        return Ok(filter);
+      }
+      filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+    }
+  }
+
+  fn parse_and(&mut self) -> Result<Filter, ParseError> {
+    let mut filter = self.parse_unary()?;
+    loop {
+      self.skip_whitespace();
+      if !self.eat_str("&&") {
+        return Ok(filter);
+      }
+      filter = Filter::And(Box::new(filter), Box::new(self.parse_unary()?));
+    }
+  }
+
+  fn parse_unary(&mut self) -> Result<Filter, ParseError> {
+    self.skip_whitespace();
+    if self.peek() == Some('!') && !self.peek_str("!=") {
+      self.position += 1;
+      return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+    }
+    if self.eat('(') {
+      let filter = self.parse_or()?;
+      self.skip_whitespace();
+      self.expect(')')?;
+      return Ok(filter);
+    }
+
+    let left = self.parse_operand()?;
+    self.skip_whitespace();
+    let comparison = if self.eat_str("==") {
+      Comparison::Eq
+    } else if self.eat_str("!=") {
+      Comparison::Ne
+    } else if self.eat_str("<=") {
+      Comparison::Le
+    } else if self.eat_str(">=") {
+      Comparison::Ge
+    } else if self.eat('<') {
+      Comparison::Lt
+    } else if self.eat('>') {
+      Comparison::Gt
+    } else {
+      return match left {
+        Operand::Literal(_) => self.error("Expected a comparison operator"),
+        path => Ok(Filter::Exists(path))
+      };
+    };
+    self.skip_whitespace();
+    let right = self.parse_operand()?;
+    Ok(Filter::Compare(left, comparison, right))
+  }
+
+  fn parse_operand(&mut self) -> Result<Operand, ParseError> {
+    match self.peek() {
+      Some('@') => {
+        self.position += 1;
+        Ok(Operand::Current(self.parse_keys()?))
+      },
+      Some('$') => {
+        self.position += 1;
+        Ok(Operand::Root(self.parse_keys()?))
+      },
+      Some('\'') | Some('"') => Ok(Operand::Literal(self.parse_string()?.into())),
+      Some(ch) if ch == '-' || ch.is_ascii_digit() => Ok(Operand::Literal(self.parse_number()?.into())),
+      _ if self.eat_str("true") => Ok(Operand::Literal(true.into())),
+      _ if self.eat_str("false") => Ok(Operand::Literal(false.into())),
+      _ if self.eat_str("null") => Ok(Operand::Literal(JsonValue::Null)),
+      _ => self.error("Expected '@', '$' or a literal")
+    }
+  }
+
+  fn parse_keys(&mut self) -> Result<Vec<Key>, ParseError> {
+    let mut keys = vec![];
+    loop {
+      if self.peek_str("..") {
+        return self.error("Recursive descent is not supported in filters");
+      }
+      if self.eat('.') {
+        keys.push(Key::Name(self.parse_name()?));
+      } else if self.eat('[') {
+        self.skip_whitespace();
+        keys.push(match self.peek() {
+          Some('\'') | Some('"') => Key::Name(self.parse_string()?),
+          _ => match self.parse_optional_integer()? {
+            Some(index) => Key::Index(index),
+            None => return self.error("Expected an index or a quoted name")
+          }
+        });
+        self.skip_whitespace();
+        self.expect(']')?;
+      } else {
+        return Ok(keys);
+      }
+    }
+  }
+
+  fn parse_number(&mut self) -> Result<f64, ParseError> {
+    let start = self.position;
+    while self.peek().is_some_and(|ch| ch.is_ascii_digit() || "-+.eE".contains(ch)) {
+      self.position += 1;
+    }
+    let number: String = self.chars[start..self.position].iter().collect();
+    match number.parse() {
+      Ok(number) => Ok(number),
+      Err(_) => {
+        self.position = start;
+        self.error("Invalid number")
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use json::*;
+  use std::ptr;
+
+  fn input() -> JsonValue {
+    object!{
+      "id" => 1,
+      "items" => array![
+        object!{ "id" => 2, "price" => 10, "status" => "ok" },
+        object!{ "id" => 3, "price" => 25.5, "status" => "failed" },
+        object!{ "id" => 4, "price" => 7, "status" => "failed" }
+      ]
+    }
+  }
+
+  #[test]
+  fn should_select_children_by_name_index_and_wildcard() {
+    let input = input();
+
+    let prices = select(&input, "$.items[*].price").unwrap();
+    assert_eq!(prices.len(), 3);
+    assert!(ptr::eq(prices[1], &input["items"][1]["price"]));
+
+    let last = select(&input, "$['items'][-1]").unwrap();
+    assert!(ptr::eq(last[0], &input["items"][2]));
+  }
+
+  #[test]
+  fn should_select_slices() {
+    let input = input();
+
+    assert_eq!(select(&input, "$.items[0:2]").unwrap().len(), 2);
+    assert_eq!(select(&input, "$.items[::2]").unwrap(), vec![&input["items"][0], &input["items"][2]]);
+    assert_eq!(select(&input, "$.items[::-1]").unwrap()[0], &input["items"][2]);
+    assert_eq!(select(&input, "$.items[1::9223372036854775807]").unwrap(), vec![&input["items"][1]]);
+    assert_eq!(select(&input, "$.items[::-9223372036854775808]").unwrap(), vec![&input["items"][2]]);
+  }
+
+  #[test]
+  fn should_select_recursively() {
+    let input = input();
+
+    let ids = select(&input, "$..id").unwrap();
+    assert_eq!(ids.len(), 4);
+    assert!(ptr::eq(ids[0], &input["id"]));
+    assert!(ptr::eq(ids[3], &input["items"][2]["id"]));
+  }
+
+  #[test]
+  fn should_select_with_filters() {
+    let input = input();
+
+    let failed = select(&input, "$.items[?(@.status == 'failed')]").unwrap();
+    assert_eq!(failed.len(), 2);
+    assert!(ptr::eq(failed[0], &input["items"][1]));
+
+    let cheap_or_ok = select(&input, "$.items[?(@.price < 8 || @.status == \"ok\")].id").unwrap();
+    assert_eq!(cheap_or_ok, vec![&input["items"][0]["id"], &input["items"][2]["id"]]);
+
+    let not_failed = select(&input, "$.items[?(!(@.status == 'failed') && @.price)]").unwrap();
+    assert_eq!(not_failed, vec![&input["items"][0]]);
+  }
+
+  #[test]
+  fn should_report_parse_errors() {
+    assert_eq!(
+      JsonPath::parse("items[0]"),
+      Err(ParseError { position: 0, message: "Expected '$'".to_string() })
+    );
+    assert_eq!(
+      JsonPath::parse("$.items[0"),
+      Err(ParseError { position: 9, message: "Expected ',' or ']'".to_string() })
+    );
+    assert_eq!(
+      JsonPath::parse("$.items[?(@.price <)]").unwrap_err().position,
+      19
+    );
+  }
+}
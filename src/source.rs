@@ -0,0 +1,202 @@
+use std::char;
+use std::ops::Range;
+use std::result;
+
+/// Where a node of a JSON document was written in its source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceNode {
+  /// Byte range of the node in the source, including the quotes of strings
+  /// and the brackets of arrays and objects
+  pub range: Range<usize>,
+  pub children: SourceChildren
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceChildren {
+  None,
+  Array(Vec<SourceNode>),
+  /// Members in the order they were written, with their keys unescaped
+  Object(Vec<(String, SourceNode)>)
+}
+
+impl SourceNode {
+  /// The member written at `key`, the last one when the key is repeated
+  pub fn get(&self, key: &str) -> Option<&SourceNode> {
+    match self.children {
+      SourceChildren::Object(ref members) => members.iter().rev().find(|(member, _)| member == key).map(|(_, node)| node),
+      _ => None
+    }
+  }
+
+  pub fn item(&self, index: usize) -> Option<&SourceNode> {
+    match self.children {
+      SourceChildren::Array(ref items) => items.get(index),
+      _ => None
+    }
+  }
+}
+
+/// Parses JSON text, mapping every node to where it was written.
+/// The text is checked with `json::parse` first, so it fails with the same errors.
+pub fn parse(source: &str) -> result::Result<SourceNode, json::Error> {
+  json::parse(source)?;
+  let mut parser = Parser { source, bytes: source.as_bytes(), index: 0 };
+  parser.skip_whitespace();
+  Ok(parser.parse_value())
+}
+
+// Finds where the nodes of text `json::parse` has accepted were written, so it never fails
+struct Parser<'a> {
+  source: &'a str,
+  bytes: &'a [u8],
+  index: usize
+}
+
+impl<'a> Parser<'a> {
+  // The same whitespace as json-rust, which includes vertical tabs and form feeds
+  fn skip_whitespace(&mut self) {
+    while let Some(9..=13) | Some(b' ') = self.bytes.get(self.index) {
+      self.index += 1;
+    }
+  }
+
+  fn parse_value(&mut self) -> SourceNode {
+    let start = self.index;
+    let children = match self.bytes[self.index] {
+      b'{' => self.parse_object(),
+      b'[' => self.parse_array(),
+      b'"' => {
+        self.parse_string();
+        SourceChildren::None
+      },
+      // Numbers and literals
+      _ => {
+        while let Some(b'-' | b'+' | b'.' | b'0'..=b'9' | b'a'..=b'z' | b'E') = self.bytes.get(self.index) {
+          self.index += 1;
+        }
+        SourceChildren::None
+      }
+    };
+    SourceNode { range: start..self.index, children }
+  }
+
+  fn parse_object(&mut self) -> SourceChildren {
+    let mut members = vec![];
+    self.index += 1;
+    self.skip_whitespace();
+    while self.bytes[self.index] != b'}' {
+      let key = self.parse_string();
+      self.skip_whitespace();
+      // Past the colon
+      self.index += 1;
+      self.skip_whitespace();
+      members.push((key, self.parse_value()));
+      self.skip_separator();
+    }
+    self.index += 1;
+    SourceChildren::Object(members)
+  }
+
+  fn parse_array(&mut self) -> SourceChildren {
+    let mut items = vec![];
+    self.index += 1;
+    self.skip_whitespace();
+    while self.bytes[self.index] != b']' {
+      items.push(self.parse_value());
+      self.skip_separator();
+    }
+    self.index += 1;
+    SourceChildren::Array(items)
+  }
+
+  // Skips the comma after a member or item, if there is one, and the whitespace around it
+  fn skip_separator(&mut self) {
+    self.skip_whitespace();
+    if self.bytes[self.index] == b',' {
+      self.index += 1;
+      self.skip_whitespace();
+    }
+  }
+
+  // Returns the unescaped string, so that keys can be looked up
+  fn parse_string(&mut self) -> String {
+    self.index += 1;
+    let mut string = String::new();
+    let mut unescaped_from = self.index;
+    loop {
+      match self.bytes[self.index] {
+        b'"' => {
+          string.push_str(&self.source[unescaped_from..self.index]);
+          self.index += 1;
+          return string;
+        },
+        b'\\' => {
+          string.push_str(&self.source[unescaped_from..self.index]);
+          let escape = self.bytes[self.index + 1];
+          self.index += 2;
+          string.push(match escape {
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => self.parse_unicode_escape(),
+            _ => escape as char
+          });
+          unescaped_from = self.index;
+        },
+        _ => self.index += 1
+      }
+    }
+  }
+
+  // Decodes the hex digits following `\u`, combining surrogate pairs into a single character
+  fn parse_unicode_escape(&mut self) -> char {
+    let code = self.parse_hex_digits();
+    if !(0xd800..0xdc00).contains(&code) || !self.bytes[self.index..].starts_with(b"\\u") {
+      return char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER);
+    }
+    self.index += 2;
+    let low = self.parse_hex_digits();
+    char::decode_utf16(vec![code as u16, low as u16])
+      .next()
+      .and_then(|decoded| decoded.ok())
+      .unwrap_or(char::REPLACEMENT_CHARACTER)
+  }
+
+  fn parse_hex_digits(&mut self) -> u32 {
+    let digits = &self.source[self.index..self.index + 4];
+    self.index += 4;
+    u32::from_str_radix(digits, 16).unwrap_or(0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_map_nodes_to_their_source() {
+    let source = "{\n  \"code\": 2.50e1,\n  \"tags\": [ \"a\\u00e9\", null ],\n  \"code\": -0\n}\n";
+    let root = parse(source).unwrap();
+
+    assert_eq!(root.range, 0..source.len() - 1);
+    assert_eq!(&source[root.get("tags").unwrap().range.clone()], "[ \"a\\u00e9\", null ]");
+    assert_eq!(&source[root.get("tags").unwrap().item(0).unwrap().range.clone()], "\"a\\u00e9\"");
+    assert_eq!(&source[root.get("code").unwrap().range.clone()], "-0");
+    assert_eq!(root.get("missing"), None);
+  }
+
+  #[test]
+  fn should_unescape_keys() {
+    let root = parse(r#"{"a\"é😀\n": 1}"#).unwrap();
+
+    assert!(root.get("a\"é😀\n").is_some());
+  }
+
+  #[test]
+  fn should_accept_what_json_parse_accepts() {
+    assert_eq!(parse("[1]\u{c}").map(|root| root.range), Ok(0..3));
+    assert_eq!(parse("\u{b}[\"\\ud83d\\ude00\"]").map(|root| root.range), Ok(1..17));
+  }
+}
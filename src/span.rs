@@ -0,0 +1,78 @@
+use std::cmp::Reverse;
+use std::ops::Range;
+
+use crate::style::Style;
+
+/// A position in the rendered text, counting lines and characters from zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize
+}
+
+/// Where a highlighted slice was written in the plain text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+  /// Byte range of the slice in the text
+  pub range: Range<usize>,
+  pub slice_index: usize,
+  pub style: Style,
+  pub start: Position,
+  pub end: Position
+}
+
+/// The plain, uncolored JSON text, along with the spans of every highlighted slice
+/// in the order they start. Spans of nested slices overlap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanMap {
+  pub text: String,
+  pub spans: Vec<Span>
+}
+
+impl SpanMap {
+  pub(crate) fn new(text: String, highlights: Vec<(Range<usize>, usize, Style)>) -> Self {
+    let line_starts : Vec<usize> = std::iter::once(0)
+      .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+      .collect();
+    let mut spans : Vec<Span> = highlights.into_iter()
+      .map(|(range, slice_index, style)| Span {
+        start: position(&text, &line_starts, range.start),
+        end: position(&text, &line_starts, range.end),
+        range,
+        slice_index,
+        style
+      })
+      .collect();
+    spans.sort_by_key(|span| (span.range.start, Reverse(span.range.end)));
+    SpanMap {
+      text,
+      spans
+    }
+  }
+}
+
+fn position(text: &str, line_starts: &[usize], offset: usize) -> Position {
+  let line = match line_starts.binary_search(&offset) {
+    Ok(line) => line,
+    Err(next_line) => next_line - 1
+  };
+  Position {
+    line,
+    column: text[line_starts[line]..offset].chars().count()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use colored::Color;
+
+  #[test]
+  fn should_find_lines_and_columns_of_spans() {
+    let map = SpanMap::new("{\n  \"é\": true\n}".to_string(), vec![(10..14, 0, Color::Red.into())]);
+
+    assert_eq!(&map.text[map.spans[0].range.clone()], "true");
+    assert_eq!(map.spans[0].start, Position { line: 1, column: 7 });
+    assert_eq!(map.spans[0].end, Position { line: 1, column: 11 });
+  }
+}
@@ -0,0 +1,94 @@
+use colored::*;
+
+/// How a piece of text is written: its foreground and background colors, and text attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+  pub foreground: Option<Color>,
+  pub background: Option<Color>,
+  pub bold: bool,
+  pub italic: bool,
+  pub underline: bool,
+  pub reversed: bool
+}
+
+impl Style {
+  pub fn new() -> Self {
+    Style::default()
+  }
+
+  pub fn fg(mut self, color: Color) -> Self {
+    self.foreground = Some(color);
+    self
+  }
+
+  pub fn on(mut self, color: Color) -> Self {
+    self.background = Some(color);
+    self
+  }
+
+  pub fn bold(mut self) -> Self {
+    self.bold = true;
+    self
+  }
+
+  pub fn italic(mut self) -> Self {
+    self.italic = true;
+    self
+  }
+
+  pub fn underline(mut self) -> Self {
+    self.underline = true;
+    self
+  }
+
+  pub fn reversed(mut self) -> Self {
+    self.reversed = true;
+    self
+  }
+
+  pub fn paint(&self, text: &str) -> ColoredString {
+    let mut painted = text.normal();
+    if let Some(color) = self.foreground {
+      painted = painted.color(color);
+    }
+    if let Some(color) = self.background {
+      painted = painted.on_color(color);
+    }
+    if self.bold {
+      painted = painted.bold();
+    }
+    if self.italic {
+      painted = painted.italic();
+    }
+    if self.underline {
+      painted = painted.underline();
+    }
+    if self.reversed {
+      painted = painted.reversed();
+    }
+    painted
+  }
+}
+
+impl From<Color> for Style {
+  fn from(color: Color) -> Self {
+    Style::new().fg(color)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn should_paint_text_with_every_attribute() {
+    assert_eq!(
+      Style::from(Color::Red).paint("text").to_string(),
+      "text".red().to_string()
+    );
+    assert_eq!(
+      Style::new().fg(Color::Black).on(Color::Yellow).bold().underline().paint("text").to_string(),
+      "text".black().on_yellow().bold().underline().to_string()
+    );
+  }
+}
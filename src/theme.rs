@@ -0,0 +1,56 @@
+use colored::Color;
+
+use crate::style::Style;
+
+/// The kinds of tokens a JSON object is written as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Token {
+  Key,
+  String,
+  Number,
+  Boolean,
+  Null,
+  Punctuation
+}
+
+/// Styles for syntax highlighting each kind of token.
+/// Highlighted slices are written in their own style, on top of the theme.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Theme {
+  pub key: Option<Style>,
+  pub string: Option<Style>,
+  pub number: Option<Style>,
+  pub boolean: Option<Style>,
+  pub null: Option<Style>,
+  pub punctuation: Option<Style>
+}
+
+impl Theme {
+  /// A theme which leaves every token uncolored, ready to be configured
+  pub fn new() -> Self {
+    Theme::default()
+  }
+
+  /// A theme which leaves red, the default highlight color, free for matches
+  pub fn classic() -> Self {
+    Theme {
+      key: Some(Color::Blue.into()),
+      string: Some(Color::Green.into()),
+      number: Some(Color::Cyan.into()),
+      boolean: Some(Color::Yellow.into()),
+      null: Some(Style::new().fg(Color::Magenta).italic()),
+      punctuation: Some(Color::BrightBlack.into())
+    }
+  }
+
+  pub fn get(&self, token: Token) -> Option<Style> {
+    match token {
+      Token::Key => self.key,
+      Token::String => self.string,
+      Token::Number => self.number,
+      Token::Boolean => self.boolean,
+      Token::Null => self.null,
+      Token::Punctuation => self.punctuation
+    }
+  }
+}
@@ -0,0 +1,240 @@
+use std::io;
+use json::JsonValue;
+
+use crate::generator::print_dec;
+
+/// What a node of a JSON document holds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Node<'a> {
+  Null,
+  Boolean(bool),
+  /// A number, which is written by `JsonTree::write_number`
+  Number,
+  String(&'a str),
+  /// An array, whose items are iterated by `JsonTree::items`
+  Array,
+  /// An object, whose entries are iterated by `JsonTree::entries`
+  Object
+}
+
+/// The items of an array, in order
+pub type Items<'a, T> = Box<dyn Iterator<Item = &'a T> + 'a>;
+
+/// The keys and values of an object, in the order they are written
+pub type Entries<'a, T> = Box<dyn Iterator<Item = (&'a str, &'a T)> + 'a>;
+
+/// A JSON document model which can be highlighted.
+/// Slices are matched by identity, so they must borrow from the document being written.
+/// Children are iterated in place, so large documents are never copied while they are written.
+pub trait JsonTree: Sized {
+  fn node(&self) -> Node<'_>;
+
+  /// Only called on nodes which are a `Node::Array`
+  fn items(&self) -> Items<'_, Self>;
+
+  /// Only called on nodes which are a `Node::Object`
+  fn entries(&self) -> Entries<'_, Self>;
+
+  /// Writes the number this node holds. Only called on nodes which are a `Node::Number`.
+  fn write_number(&self, writer: &mut dyn io::Write) -> io::Result<()>;
+}
+
+impl JsonTree for JsonValue {
+  fn node(&self) -> Node<'_> {
+    match *self {
+      JsonValue::Null => Node::Null,
+      JsonValue::Boolean(boolean) => Node::Boolean(boolean),
+      JsonValue::Number(_) => Node::Number,
+      JsonValue::Short(ref short) => Node::String(short.as_str()),
+      JsonValue::String(ref string) => Node::String(string),
+      JsonValue::Array(_) => Node::Array,
+      JsonValue::Object(_) => Node::Object
+    }
+  }
+
+  fn items(&self) -> Items<'_, Self> {
+    Box::new(self.members())
+  }
+
+  fn entries(&self) -> Entries<'_, Self> {
+    Box::new(JsonValue::entries(self))
+  }
+
+  fn write_number(&self, mut writer: &mut dyn io::Write) -> io::Result<()> {
+    match *self {
+      JsonValue::Number(ref number) if number.is_nan() => writer.write_all(b"null"),
+      JsonValue::Number(ref number) => {
+        let (positive, mantissa, exponent) = number.as_parts();
+        unsafe {
+          print_dec::write(&mut writer, positive, mantissa, exponent)
+        }
+      },
+      _ => Ok(())
+    }
+  }
+}
+
+#[cfg(feature = "serde_json")]
+impl JsonTree for serde_json::Value {
+  fn node(&self) -> Node<'_> {
+    match *self {
+      serde_json::Value::Null => Node::Null,
+      serde_json::Value::Bool(boolean) => Node::Boolean(boolean),
+      serde_json::Value::Number(_) => Node::Number,
+      serde_json::Value::String(ref string) => Node::String(string),
+      serde_json::Value::Array(_) => Node::Array,
+      serde_json::Value::Object(_) => Node::Object
+    }
+  }
+
+  fn items(&self) -> Items<'_, Self> {
+    match *self {
+      serde_json::Value::Array(ref array) => Box::new(array.iter()),
+      _ => Box::new(std::iter::empty())
+    }
+  }
+
+  fn entries(&self) -> Entries<'_, Self> {
+    match *self {
+      serde_json::Value::Object(ref object) => Box::new(object.iter().map(|(key, value)| (key.as_str(), value))),
+      _ => Box::new(std::iter::empty())
+    }
+  }
+
+  fn write_number(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+    match *self {
+      serde_json::Value::Number(ref number) => write!(writer, "{}", number),
+      _ => Ok(())
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use colored::*;
+  use crate::{highlight, highlight_where};
+
+  // A document model whose nodes borrow their children, as when they are allocated in an arena
+  enum Doc<'a> {
+    Missing,
+    Count(u32),
+    Label(&'a str),
+    List(Vec<&'a Doc<'a>>),
+    Record(Vec<(&'a str, &'a Doc<'a>)>)
+  }
+
+  impl<'a> JsonTree for Doc<'a> {
+    fn node(&self) -> Node<'_> {
+      match self {
+        Doc::Missing => Node::Null,
+        Doc::Count(_) => Node::Number,
+        Doc::Label(label) => Node::String(label),
+        Doc::List(_) => Node::Array,
+        Doc::Record(_) => Node::Object
+      }
+    }
+
+    fn items(&self) -> Items<'_, Self> {
+      match self {
+        Doc::List(items) => Box::new(items.iter().copied()),
+        _ => Box::new(std::iter::empty())
+      }
+    }
+
+    fn entries(&self) -> Entries<'_, Self> {
+      match self {
+        Doc::Record(fields) => Box::new(fields.iter().copied()),
+        _ => Box::new(std::iter::empty())
+      }
+    }
+
+    fn write_number(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+      match self {
+        Doc::Count(count) => write!(writer, "{}", count),
+        _ => Ok(())
+      }
+    }
+  }
+
+  #[test]
+  fn should_highlight_custom_document_models() {
+    let (first, second, missing) = (Doc::Count(1), Doc::Label("two \"2\""), Doc::Missing);
+    let list = Doc::List(vec![&first, &second]);
+    let root = Doc::Record(vec![("list", &list), ("missing", &missing)]);
+
+    assert_eq!(
+      highlight(&root, vec![&second]),
+      format!(
+        "{}{}{}",
+        r#"{"list":[1,"#,
+        r#""two \"2\"""#.red(),
+        r#"],"missing":null}"#
+      )
+    );
+    assert_eq!(
+      highlight_where(&root, |path, node| match node {
+        Doc::Count(_) => Some(Color::Green),
+        Doc::Missing if path.depth() == 1 => Some(Color::Yellow),
+        _ => None
+      }),
+      format!(
+        "{}{}{}{}{}",
+        r#"{"list":["#,
+        "1".green(),
+        r#","two \"2\""],"missing":"#,
+        "null".yellow(),
+        "}"
+      )
+    );
+  }
+
+  #[cfg(feature = "serde_json")]
+  #[test]
+  fn should_highlight_serde_json_values() {
+    use serde_json::json;
+    use crate::{highlight_with_colors, highlight_with_colors_and_remainder};
+
+    let input = json!({
+      "code": 200,
+      "payload": { "features": ["awesome", "easyAPI"], "ratio": 0.5, "legacy": null }
+    });
+
+    assert_eq!(
+      highlight(&input, vec![&input["payload"]["features"][1]]),
+      format!(
+        "{}{}{}",
+        r#"{"code":200,"payload":{"features":["awesome","#,
+        r#""easyAPI""#.red(),
+        r#"],"legacy":null,"ratio":0.5}}"#
+      )
+    );
+    assert_eq!(
+      highlight_with_colors(&input, vec![&input["code"], &input["payload"]["legacy"]], vec![Color::Green, Color::Blue]),
+      format!(
+        "{}{}{}{}{}",
+        r#"{"code":"#,
+        "200".green(),
+        r#","payload":{"features":["awesome","easyAPI"],"legacy":"#,
+        "null".blue(),
+        r#","ratio":0.5}}"#
+      )
+    );
+    assert_eq!(
+      highlight_with_colors_and_remainder(&input, vec![&input["code"]], None, Some(Color::White)),
+      format!(
+        "{}{}{}",
+        r#"{"code":"#.white(),
+        "200".red(),
+        r#","payload":{"features":["awesome","easyAPI"],"legacy":null,"ratio":0.5}}"#.white()
+      )
+    );
+
+    let source = "{ \"zeta\": 1, \"alpha\": [true] }";
+    let input : serde_json::Value = serde_json::from_str(source).unwrap();
+    assert_eq!(
+      crate::try_highlight_source(source, &input, vec![&input["alpha"][0], &input["zeta"]]).unwrap(),
+      format!("{}{}{}{}{}", r#"{ "zeta": "#, "1".red(), r#", "alpha": ["#, "true".red(), "] }")
+    );
+  }
+}